@@ -0,0 +1,205 @@
+use proc_macro2::Ident;
+use syn::{ext::IdentExt, LitStr, Member};
+
+use crate::one_of;
+
+#[derive(Clone, Copy)]
+pub(crate) enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+const RENAME_RULES: &[(&str, RenameRule)] = &[
+    ("lowercase", RenameRule::Lower),
+    ("UPPERCASE", RenameRule::Upper),
+    ("PascalCase", RenameRule::Pascal),
+    ("camelCase", RenameRule::Camel),
+    ("snake_case", RenameRule::Snake),
+    ("SCREAMING_SNAKE_CASE", RenameRule::ScreamingSnake),
+    ("kebab-case", RenameRule::Kebab),
+    ("SCREAMING-KEBAB-CASE", RenameRule::ScreamingKebab),
+];
+
+impl RenameRule {
+    pub(crate) fn parse(lit: &LitStr) -> syn::Result<Self> {
+        let value = lit.value();
+        RENAME_RULES
+            .iter()
+            .find(|(name, _)| *name == value)
+            .map(|(_, rule)| *rule)
+            .ok_or_else(|| {
+                let names: Vec<_> =
+                    RENAME_RULES.iter().map(|(name, _)| *name).collect();
+                syn::Error::new_spanned(
+                    lit,
+                    format!(
+                        "unknown case `{}`, expected {}",
+                        value,
+                        one_of(&names)
+                    ),
+                )
+            })
+    }
+
+    pub(crate) fn apply(self, name: &str) -> String {
+        let words = words(name);
+        let words = words.iter().enumerate().map(|(index, word)| match self {
+            RenameRule::Lower | RenameRule::Snake | RenameRule::Kebab => {
+                word.to_lowercase()
+            }
+            RenameRule::Upper
+            | RenameRule::ScreamingSnake
+            | RenameRule::ScreamingKebab => word.to_uppercase(),
+            RenameRule::Camel if index == 0 => word.to_lowercase(),
+            RenameRule::Pascal | RenameRule::Camel => capitalize(word),
+        });
+        let separator = match self {
+            RenameRule::Snake | RenameRule::ScreamingSnake => "_",
+            RenameRule::Kebab | RenameRule::ScreamingKebab => "-",
+            _ => "",
+        };
+        words.collect::<Vec<_>>().join(separator)
+    }
+}
+
+// Splits `PascalCase`, `camelCase`, `snake_case` or `kebab-case` names into
+// words, keeping acronyms like the `HTTP` of `HTTPServer` together.
+fn words(name: &str) -> Vec<&str> {
+    let chars: Vec<_> = name.char_indices().collect();
+    let mut words = vec![];
+    let mut start = None::<usize>;
+    for (position, &(index, c)) in chars.iter().enumerate() {
+        if c == '_' || c == '-' {
+            if let Some(start) = start.take() {
+                words.push(&name[start..index]);
+            }
+            continue;
+        }
+        if let Some(word_start) = start {
+            let prev = chars[position - 1].1;
+            let next = chars.get(position + 1).map(|&(_, c)| c);
+            let boundary = c.is_uppercase()
+                && (!prev.is_uppercase()
+                    || next.is_some_and(char::is_lowercase));
+            if boundary {
+                words.push(&name[word_start..index]);
+                start = Some(index);
+            }
+        } else {
+            start = Some(index);
+        }
+    }
+    if let Some(start) = start {
+        words.push(&name[start..]);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+// Rules deriving the foreign name of every field or variant without an
+// explicit `rename`.
+#[derive(Clone, Default)]
+pub(crate) struct RenameRules {
+    pub(crate) rename_all: Option<RenameRule>,
+    pub(crate) prefix: Option<String>,
+    pub(crate) suffix: Option<String>,
+}
+
+impl RenameRules {
+    pub(crate) fn apply(&self, name: &str) -> String {
+        let name = match self.rename_all {
+            Some(rule) => rule.apply(name),
+            None => name.to_owned(),
+        };
+        format!(
+            "{}{}{}",
+            self.prefix.as_deref().unwrap_or_default(),
+            name,
+            self.suffix.as_deref().unwrap_or_default()
+        )
+    }
+
+    pub(crate) fn apply_member(&self, member: &Member) -> Member {
+        match member {
+            Member::Named(ident) if !self.is_empty() => Member::Named(
+                self.rename_ident(ident)
+                    .expect("renamed members are checked by `check_members`"),
+            ),
+            _ => member.clone(),
+        }
+    }
+
+    // The foreign name of `ident`, raw for keywords, or `None` when the rules
+    // don't produce an identifier.
+    fn rename_ident(&self, ident: &Ident) -> Option<Ident> {
+        let name = self.apply(&ident.unraw().to_string());
+        let mut renamed = syn::parse_str::<Ident>(&name)
+            .or_else(|_| syn::parse_str::<Ident>(&format!("r#{}", name)))
+            .ok()?;
+        renamed.set_span(ident.span());
+        Some(renamed)
+    }
+
+    // Fails when the rules turn one of `members` into something that isn't
+    // an identifier.
+    pub(crate) fn check_members<'a>(
+        &self,
+        members: impl IntoIterator<Item = &'a Ident>,
+    ) -> syn::Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        for ident in members {
+            if self.rename_ident(ident).is_none() {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    format!(
+                        "`rename_all`, `prefix` and `suffix` turn `{}` \
+                        into `{}`, which isn't an identifier",
+                        ident.unraw(),
+                        self.apply(&ident.unraw().to_string())
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn is_empty(&self) -> bool {
+        self.rename_all.is_none()
+            && self.prefix.is_none()
+            && self.suffix.is_none()
+    }
+
+    // Fails when the rules can't produce Rust identifiers.
+    pub(crate) fn check_idents(&self, key: &Ident) -> syn::Result<()> {
+        let kebab = matches!(
+            self.rename_all,
+            Some(RenameRule::Kebab | RenameRule::ScreamingKebab)
+        );
+        if kebab || syn::parse_str::<Ident>(&self.apply("x")).is_err() {
+            return Err(syn::Error::new_spanned(
+                key,
+                "`rename_all`, `prefix` and `suffix` must produce identifiers, \
+                unless the key is `by_name`",
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,176 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{ext::IdentExt, Member, Type, TypeReference};
+
+use super::{
+    quote_unmapped,
+    repr::{quote_from_self, unexpected_options, ReprOp, ReprVariant},
+    AllVariantsOptions,
+};
+use crate::{path_eq, type_path, Repr, TypeRef};
+
+struct Variant<'a> {
+    name: &'a Member,
+    // the canonical string first, then its aliases
+    strings: Vec<TokenStream2>,
+    skip: bool,
+}
+
+fn names<'a, VO: ReprOp, FO>(
+    variants: &'a AllVariantsOptions<VO, FO>,
+    TypeRef { key, rename, .. }: TypeRef,
+    allow_skip: bool,
+) -> syn::Result<Vec<Variant<'a>>> {
+    variants
+        .c_like(key, Repr::Name.option())?
+        .into_iter()
+        .map(|(name, repr)| {
+            let implicit = || rename.apply(&member_string(name));
+            let (strings, skip) = match repr {
+                ReprVariant::Implicit { rename, aliases } => {
+                    let canonical = match rename {
+                        Some(rename) => member_string(rename),
+                        None => implicit(),
+                    };
+                    let strings = std::iter::once(canonical)
+                        .chain(aliases.iter().map(member_string))
+                        .map(|string| quote!(#string))
+                        .collect();
+                    (strings, false)
+                }
+                ReprVariant::Name(expr) => (vec![quote!(#expr)], false),
+                ReprVariant::Skip if allow_skip => {
+                    let canonical = implicit();
+                    (vec![quote!(#canonical)], true)
+                }
+                _ => {
+                    let expected = if allow_skip {
+                        "`rename`, `aliases`, `name` and `skip`"
+                    } else {
+                        "`rename` and `name`"
+                    };
+                    return Err(unexpected_options(
+                        name,
+                        key,
+                        Repr::Name.option(),
+                        expected,
+                    ));
+                }
+            };
+            Ok(Variant {
+                name,
+                strings,
+                skip,
+            })
+        })
+        .collect()
+}
+
+fn member_string(member: &Member) -> String {
+    match member {
+        Member::Named(ident) => ident.unraw().to_string(),
+        Member::Unnamed(index) => index.index.to_string(),
+    }
+}
+
+// `TryFrom<RAW> for Self`, the infallible `From<Self> for RAW` and, when `RAW`
+// is `&str`, `FromStr for Self`.
+pub(super) fn derive_try_from<VO: ReprOp, FO>(
+    variants: &AllVariantsOptions<VO, FO>,
+    type_ref @ TypeRef {
+        from,
+        to,
+        case_insensitive,
+        ..
+    }: TypeRef,
+    err_ty: &Type,
+) -> syn::Result<TokenStream2> {
+    let variants = names(variants, type_ref, true)?;
+    let generics = type_ref.generics();
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let to_path = type_path(to);
+    let lines = variants.iter().filter(|variant| !variant.skip).map(
+        |Variant { name, strings, .. }| {
+            if case_insensitive {
+                quote!(
+                    value if #(value.eq_ignore_ascii_case(#strings))||* =>
+                        #to_path::#name,
+                )
+            } else {
+                quote!(#(#strings)|* => #to_path::#name,)
+            }
+        },
+    );
+    let unmapped = quote_unmapped(type_ref.unmapped);
+    let lines = quote!(
+        #(#lines)*
+        #unmapped
+    );
+    let from_str = if is_str_ref(from) {
+        quote! {
+            impl #impl_generics std::str::FromStr for #to #where_clause {
+                type Err = #err_ty;
+
+                fn from_str(value: &str) -> Result<#to, Self::Err> {
+                    Ok(match value {
+                        #lines
+                    })
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
+    let from_self = quote_from_self(
+        variants.iter().map(|Variant { name, strings, .. }| {
+            let string = &strings[0];
+            (*name, quote!(#string.into()))
+        }),
+        type_ref,
+        to,
+        from,
+    );
+    Ok(quote! {
+        impl #impl_generics std::convert::TryFrom<#from> for #to #where_clause {
+            type Error = #err_ty;
+
+            fn try_from(value: #from) -> Result<#to, Self::Error> {
+                Ok(match std::convert::AsRef::<str>::as_ref(&value) {
+                    #lines
+                })
+            }
+        }
+
+        #from_str
+
+        #from_self
+    })
+}
+
+// `From<Self> for RAW`.
+pub(super) fn derive_from_self<VO: ReprOp, FO>(
+    variants: &AllVariantsOptions<VO, FO>,
+    type_ref @ TypeRef { from, to, .. }: TypeRef,
+) -> syn::Result<TokenStream2> {
+    let variants = names(variants, type_ref, false)?;
+    Ok(quote_from_self(
+        variants.iter().map(|Variant { name, strings, .. }| {
+            let string = &strings[0];
+            (*name, quote!(#string.into()))
+        }),
+        type_ref,
+        from,
+        to,
+    ))
+}
+
+fn is_str_ref(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(TypeReference {
+            mutability: None,
+            elem,
+            ..
+        }) => matches!(&**elem, Type::Path(path) if path_eq(&path.path, "str")),
+        _ => false,
+    }
+}
@@ -0,0 +1,204 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::{Expr, Member, Type};
+
+use super::{
+    quote_unmapped,
+    repr::{quote_from_self, unexpected_options, ReprOp, ReprVariant},
+    AllVariantsOptions,
+};
+use crate::{type_path, Repr, TypeRef};
+
+struct Variant<'a> {
+    name: &'a Member,
+    value: Option<&'a Expr>,
+    skip: bool,
+}
+
+fn discriminants<'a, VO: ReprOp, FO>(
+    variants: &'a AllVariantsOptions<VO, FO>,
+    key: &syn::Ident,
+    allow_skip: bool,
+) -> syn::Result<Vec<Variant<'a>>> {
+    variants
+        .c_like(key, Repr::Discriminant.option())?
+        .into_iter()
+        .map(|(name, repr)| {
+            let (value, skip) = match repr {
+                ReprVariant::Implicit {
+                    rename: None,
+                    aliases: [],
+                } => (None, false),
+                ReprVariant::Value(expr) => (Some(expr), false),
+                ReprVariant::Skip if allow_skip => (None, true),
+                _ => {
+                    let expected = if allow_skip {
+                        "`value` and `skip`"
+                    } else {
+                        "`value`"
+                    };
+                    return Err(unexpected_options(
+                        name,
+                        key,
+                        Repr::Discriminant.option(),
+                        expected,
+                    ));
+                }
+            };
+            Ok(Variant { name, value, skip })
+        })
+        .collect()
+}
+
+// `TryFrom<RAW> for Self`, along with the infallible `From<Self> for RAW`.
+pub(super) fn derive_try_from<VO: ReprOp, FO>(
+    variants: &AllVariantsOptions<VO, FO>,
+    type_ref @ TypeRef { key, from, to, .. }: TypeRef,
+    err_ty: &Type,
+    repr_u128: bool,
+) -> syn::Result<TokenStream2> {
+    let variants = discriminants(variants, key, true)?;
+    let generics = type_ref.generics();
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let to_path = type_path(to);
+    let lines = variants.iter().filter(|variant| !variant.skip).map(
+        |Variant { name, value, .. }| {
+            let value = quote_value(&to_path, name, *value, from);
+            quote!(value if value == #value => #to_path::#name,)
+        },
+    );
+    let unmapped = quote_unmapped(type_ref.unmapped);
+    let from_self = quote_from_self(
+        variants.iter().map(|Variant { name, value, .. }| {
+            (*name, quote_value(&to_path, name, *value, from))
+        }),
+        type_ref,
+        to,
+        from,
+    );
+    let mut assertions = quote_assertions(&to_path, &variants, from, repr_u128);
+    assertions.extend(quote_distinct(&to_path, &variants, from));
+    let assertions = quote!(const _: () = { #(#assertions)* };);
+    Ok(quote! {
+        #assertions
+
+        impl #impl_generics std::convert::TryFrom<#from> for #to #where_clause {
+            type Error = #err_ty;
+
+            fn try_from(value: #from) -> Result<#to, Self::Error> {
+                Ok(match value {
+                    #(#lines)*
+                    #unmapped
+                })
+            }
+        }
+
+        #from_self
+    })
+}
+
+// `From<Self> for RAW`.
+pub(super) fn derive_from_self<VO: ReprOp, FO>(
+    variants: &AllVariantsOptions<VO, FO>,
+    type_ref @ TypeRef { key, from, to, .. }: TypeRef,
+    repr_u128: bool,
+) -> syn::Result<TokenStream2> {
+    let variants = discriminants(variants, key, false)?;
+    let from_path = type_path(from);
+    let from_self = quote_from_self(
+        variants.iter().map(|Variant { name, value, .. }| {
+            (*name, quote_value(&from_path, name, *value, to))
+        }),
+        type_ref,
+        from,
+        to,
+    );
+    let assertions = quote_assertions(&from_path, &variants, to, repr_u128);
+    let assertions = quote!(const _: () = { #(#assertions)* };);
+    Ok(quote! {
+        #assertions
+
+        #from_self
+    })
+}
+
+// `as` truncates, the discriminants cast to `raw` have to fit it. They read
+// the same as `i128` unless the enum is `#[repr(u128)]`, and a negative one
+// reads the same through `u128`, hence the sign checks. The explicit `value`s
+// have to be constants of `raw`.
+fn quote_assertions(
+    subject_path: &TokenStream2,
+    variants: &[Variant],
+    raw: &Type,
+    repr_u128: bool,
+) -> Vec<TokenStream2> {
+    let raw_u128 = matches!(raw, Type::Path(raw) if raw.path.is_ident("u128"));
+    variants
+        .iter()
+        .filter_map(|Variant { name, value, .. }| {
+            if let Some(value) = value {
+                return Some(quote!(let _: #raw = #value;));
+            }
+            let variant = quote!(#subject_path::#name);
+            let fits = match (repr_u128, raw_u128) {
+                (false, false) => {
+                    quote!((#variant as #raw) as i128 == #variant as i128)
+                }
+                (false, true) => quote!(#variant as i128 >= 0),
+                (true, false) => quote!(
+                    (#variant as #raw) as i128 >= 0
+                        && ((#variant as #raw) as i128) as u128
+                            == #variant as u128
+                ),
+                (true, true) => return None,
+            };
+            let message = format!(
+                "the discriminant of `{}` doesn't fit `{}`",
+                quote!(#name),
+                quote!(#raw),
+            );
+            Some(quote!(assert!(#fits, #message);))
+        })
+        .collect()
+}
+
+// No two variants read from the same value, which an explicit `value` may
+// repeat.
+fn quote_distinct(
+    subject_path: &TokenStream2,
+    variants: &[Variant],
+    raw: &Type,
+) -> Vec<TokenStream2> {
+    let variants: Vec<_> =
+        variants.iter().filter(|variant| !variant.skip).collect();
+    let mut assertions = vec![];
+    for (index, Variant { name, value, .. }) in variants.iter().enumerate() {
+        for other in &variants[index + 1..] {
+            if value.is_none() && other.value.is_none() {
+                continue;
+            }
+            let message = format!(
+                "`{}` and `{}` read from the same `{}`",
+                quote!(#name),
+                other.name.to_token_stream(),
+                quote!(#raw),
+            );
+            let this = quote_value(subject_path, name, *value, raw);
+            let other = quote_value(subject_path, other.name, other.value, raw);
+            assertions.push(quote!(assert!(#this != #other, #message);));
+        }
+    }
+    assertions
+}
+
+fn quote_value(
+    subject_path: &TokenStream2,
+    name: &Member,
+    value: Option<&Expr>,
+    raw: &Type,
+) -> TokenStream2 {
+    match value {
+        Some(value) => quote!((#value)),
+        None => quote!(#subject_path::#name as #raw),
+    }
+}
@@ -1,40 +1,69 @@
+mod by_name;
+mod discriminant;
 mod from;
+mod pair;
+mod repr;
 mod try_from;
 
 use proc_macro2::{Ident, TokenStream as TokenStream2};
 use quote::quote;
-use syn::{DataEnum, Type, TypePath};
+use syn::{Attribute, DataEnum, Generics, Member, Meta, NestedMeta, Type};
 
 use crate::{
-    parse_field_attrs, ContainerAttrs, FieldAttrs, FieldNamer, FieldOp,
-    TypeRef, Types,
+    convert_struct::AllFieldsOptions, parse_field_attrs, path_eq, type_path,
+    ContainerAttrs, FieldAttrs, FieldNamer, FieldOp, Subject, TypeRef, Types,
+    Unmapped,
 };
 
 pub(super) fn derive_convert_enum(
-    ContainerAttrs {
+    container_attrs: &ContainerAttrs,
+    subject: &Ident,
+    generics: &Generics,
+    attrs: &[Attribute],
+    data: &DataEnum,
+) -> syn::Result<TokenStream2> {
+    for (key, attr_type) in container_attrs.types().flat_map(|types| &types.0) {
+        if attr_type.fill.is_some()
+            || !attr_type.extra.is_empty()
+            || attr_type.accumulate_errors
+            || attr_type.warn_rest
+        {
+            return Err(syn::Error::new_spanned(
+                key,
+                "`fill_default`, `extra`, `accumulate_errors` and \
+                `ignore_rest(warn)` only apply to structs",
+            ));
+        }
+        if attr_type.repr.is_none() {
+            attr_type.rename.check_members(
+                data.variants.iter().map(|variant| &variant.ident),
+            )?;
+        }
+    }
+    let ContainerAttrs {
         from,
         from_self,
         try_from,
         try_from_self,
-    }: &ContainerAttrs,
-    subject: &Ident,
-    data: &DataEnum,
-) -> TokenStream2 {
-    let subject = Type::Path(TypePath {
-        qself: None,
-        path: subject.clone().into(),
-    });
+    } = container_attrs;
+    let subject = Subject::new(subject, generics);
+    let repr_u128 = repr_u128(attrs);
     [
-        from.as_ref()
-            .map(|attrs| from::derive_from_enum(attrs, &subject, data, false)),
-        from_self
-            .as_ref()
-            .map(|attrs| from::derive_from_enum(attrs, &subject, data, true)),
+        from.as_ref().map(|attrs| {
+            from::derive_from_enum(attrs, &subject, data, false, repr_u128)
+        }),
+        from_self.as_ref().map(|attrs| {
+            from::derive_from_enum(attrs, &subject, data, true, repr_u128)
+        }),
         try_from.as_ref().map(|attrs| {
-            try_from::derive_try_from_enum(attrs, &subject, data, false)
+            try_from::derive_try_from_enum(
+                attrs, &subject, data, false, repr_u128,
+            )
         }),
         try_from_self.as_ref().map(|attrs| {
-            try_from::derive_try_from_enum(attrs, &subject, data, true)
+            try_from::derive_try_from_enum(
+                attrs, &subject, data, true, repr_u128,
+            )
         }),
     ]
     .into_iter()
@@ -42,58 +71,125 @@ pub(super) fn derive_convert_enum(
     .collect()
 }
 
-struct OneVariantOptions<'a, FO> {
-    name: &'a Ident,
-    attrs: FieldAttrs<FO>,
+// Whether the enum is `#[repr(u128)]`, the only one whose discriminants
+// don't all read the same as `i128`.
+fn repr_u128(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| path_eq(&attr.path, "repr"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .any(|nested| {
+            matches!(
+                nested,
+                NestedMeta::Meta(Meta::Path(path)) if path_eq(&path, "u128")
+            )
+        })
+}
+
+pub(crate) struct VariantFields {
+    pub(crate) pattern: TokenStream2,
+    pub(crate) body: TokenStream2,
+}
+
+struct OneVariantOptions<VO, FO> {
+    name: Member,
+    attrs: FieldAttrs<VO>,
+    fields: Option<AllFieldsOptions<FO>>,
 }
 
-struct AllVariantsOptions<'a, FO> {
-    variants: Vec<OneVariantOptions<'a, FO>>,
+struct AllVariantsOptions<VO, FO> {
+    variants: Vec<OneVariantOptions<VO, FO>>,
 }
 
-impl<'a, FO: FieldOp> AllVariantsOptions<'a, FO> {
-    fn parse(data: &'a DataEnum, filter_path: &str, types: &Types) -> Self {
+impl<VO: FieldOp, FO: FieldOp> AllVariantsOptions<VO, FO> {
+    fn parse(
+        data: &DataEnum,
+        filter_path: &str,
+        types: &Types,
+    ) -> syn::Result<Self> {
         let variants = &data.variants;
         let variants = variants
             .into_iter()
             .map(|variant| {
-                if !variant.fields.is_empty() {
-                    unimplemented!("Only C-like enums are supported");
-                }
-                let attrs = parse_field_attrs(&variant.attrs, filter_path)
-                    .expect("Parse attributes to find field options");
-                attrs.check(types);
-                OneVariantOptions {
-                    name: &variant.ident,
+                let attrs =
+                    parse_field_attrs(&variant.attrs, filter_path, types)?;
+                let fields = if variant.fields.is_empty() {
+                    None
+                } else {
+                    let fields = AllFieldsOptions::parse(
+                        &variant.fields,
+                        filter_path,
+                        types,
+                    )?;
+                    fields.check_struct_only()?;
+                    Some(fields)
+                };
+                Ok(OneVariantOptions {
+                    name: Member::Named(variant.ident.clone()),
                     attrs,
-                }
+                    fields,
+                })
             })
-            .collect();
-        Self { variants }
+            .collect::<syn::Result<_>>()?;
+        Ok(Self { variants })
     }
 
     fn lines_n_fields(
         &self,
         from_self: bool,
-        TypeRef {
+        type_ref @ TypeRef {
             key,
             from,
             to,
             ignores,
+            non_exhaustive,
+            rename,
+            foreign_variants,
+            ..
         }: TypeRef,
     ) -> (TokenStream2, TokenStream2) {
         let mut foreign_fields = ignores.to_owned();
-        let lines = self.variants.iter().map(|field| {
-            let name = field.name;
+        let lines = self.variants.iter().enumerate().map(|(index, field)| {
+            let name = &field.name;
+            let fields = field
+                .fields
+                .as_ref()
+                .map(|fields| fields.variant_fields(from_self, type_ref));
+            // `variants(...)` names the foreign variant at the same index
+            let foreign_name = match foreign_variants.get(index) {
+                Some(foreign_name) => foreign_name.clone(),
+                None => rename.apply_member(name),
+            };
             let mut namer = FieldNamer {
                 from_self,
                 name,
-                foreign_field: None,
+                foreign_name: &foreign_name,
+                foreign_fields: vec![],
+                bindings: None,
+                variant: fields.as_ref(),
+                fill: false,
+                nested: &[],
+                assignments: vec![],
+                sources: &[],
+                split: &[],
+                prelude: vec![],
+                entries: vec![],
+                computed: vec![],
+                context: None,
+                accumulate: false,
+                attempts: vec![],
+                error_enum: None,
+                error_ty: None,
+                error_fields: vec![],
                 from,
                 to,
             };
             let res = field.attrs.map_for(key).quote(&mut namer);
-            foreign_fields.extend(namer.foreign_field.into_iter().cloned());
+            foreign_fields.extend(namer.foreign_fields.into_iter().cloned());
             res
         });
         let lines = quote!(
@@ -101,23 +197,56 @@ impl<'a, FO: FieldOp> AllVariantsOptions<'a, FO> {
                 #lines
             )*
         );
-        let foreign_fields = quote_foreign_fields(from, &foreign_fields);
+        // foreign `#[non_exhaustive]` enums can't be matched exhaustively
+        let foreign_fields = if non_exhaustive {
+            quote!()
+        } else {
+            quote_foreign_fields(from, &foreign_fields)
+        };
         (lines, foreign_fields)
     }
 }
 
-fn quote_foreign_fields(from: &Type, foreign_fields: &[Ident]) -> TokenStream2 {
+fn quote_foreign_fields(
+    from: &Type,
+    foreign_fields: &[Member],
+) -> TokenStream2 {
     if foreign_fields.is_empty() {
         quote!()
     } else {
+        let from = type_path(from);
         quote!(
             {
                 match &value {
                     #(
-                        #from::#foreign_fields => {},
+                        #from::#foreign_fields { .. } => {},
                     )*
                 }
             }
         )
     }
 }
+
+fn variant_pattern<'a>(
+    from: &TokenStream2,
+    names: impl Iterator<Item = &'a Member>,
+    rest: Option<&TokenStream2>,
+) -> TokenStream2 {
+    let patterns = names.map(|name| quote!(#from::#name #rest));
+    quote!(#(#patterns)|*)
+}
+
+// The last arm for foreign variants or values without a counterpart.
+fn quote_unmapped(unmapped: Option<&Unmapped>) -> TokenStream2 {
+    match unmapped {
+        Some(Unmapped::Error(expr)) => quote!(
+            #[allow(unreachable_patterns)]
+            _ => return Err(#expr),
+        ),
+        Some(Unmapped::Fallback(variant)) => quote!(
+            #[allow(unreachable_patterns)]
+            _ => Self::#variant,
+        ),
+        None => quote!(),
+    }
+}
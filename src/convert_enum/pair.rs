@@ -0,0 +1,151 @@
+use proc_macro2::{Literal, TokenStream as TokenStream2};
+use quote::{quote, ToTokens};
+use syn::Type;
+
+use super::{
+    quote_unmapped,
+    repr::{unexpected_options, ReprOp, ReprVariant},
+    AllVariantsOptions,
+};
+use crate::{type_path, PairBy, TypeRef};
+
+// `From<Foreign> for Self`, or `TryFrom` when `err_ty` is given, comparing
+// the foreign discriminant with the discriminant of each variant.
+pub(super) fn derive_foreign_to_self<VO: ReprOp, FO>(
+    variants: &AllVariantsOptions<VO, FO>,
+    type_ref @ TypeRef { key, from, to, .. }: TypeRef,
+    pair_by: PairBy,
+    err_ty: Option<&Type>,
+) -> syn::Result<TokenStream2> {
+    // the foreign value only tells its discriminant, the declaration order
+    // of the foreign enum is out of reach
+    if let PairBy::Index = pair_by {
+        return Err(syn::Error::new_spanned(
+            key,
+            "`pair_by = \"index\"` only applies to `from_self` and \
+            `try_from_self`, use `pair_by = \"discriminant\"` with `value`",
+        ));
+    }
+    let to_path = type_path(to);
+    let mut lines = vec![];
+    for (name, repr) in variants.c_like(key, "pair_by")? {
+        let value = match repr {
+            ReprVariant::Implicit {
+                rename: None,
+                aliases: [],
+            } => quote!(#to_path::#name as i128),
+            ReprVariant::Value(expr) => quote!((#expr)),
+            ReprVariant::Skip => continue,
+            _ => {
+                return Err(unexpected_options(
+                    name,
+                    key,
+                    "pair_by",
+                    "`value` and `skip`",
+                ))
+            }
+        };
+        lines.push(quote!(value if value == #value => #to_path::#name,));
+    }
+    let unmapped = quote_unmapped(type_ref.unmapped);
+    let body = quote!(
+        match value as i128 {
+            #(#lines)*
+            #unmapped
+        }
+    );
+    let generics = type_ref.generics();
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    Ok(match err_ty {
+        Some(err_ty) => quote! {
+            impl #impl_generics std::convert::TryFrom<#from> for #to #where_clause {
+                type Error = #err_ty;
+
+                fn try_from(value: #from) -> Result<#to, Self::Error> {
+                    Ok(#body)
+                }
+            }
+        },
+        None => quote! {
+            impl #impl_generics std::convert::From<#from> for #to #where_clause {
+                fn from(value: #from) -> #to {
+                    #body
+                }
+            }
+        },
+    })
+}
+
+// Checks at compile time that each variant converted into its foreign
+// counterpart pairs with it by discriminant or index, the foreign
+// discriminants have to be the declaration indexes for the latter.
+pub(super) fn quote_assertions<VO: ReprOp, FO>(
+    variants: &AllVariantsOptions<VO, FO>,
+    TypeRef {
+        key,
+        from,
+        to,
+        rename: rules,
+        foreign_variants,
+        ..
+    }: TypeRef,
+    pair_by: PairBy,
+) -> syn::Result<TokenStream2> {
+    let (from_path, to_path) = (type_path(from), type_path(to));
+    let mut assertions = vec![];
+    for (index, (name, repr)) in
+        variants.c_like(key, "pair_by")?.into_iter().enumerate()
+    {
+        let other = match repr {
+            ReprVariant::Implicit {
+                rename: Some(rename),
+                ..
+            } => rename.clone(),
+            ReprVariant::Implicit { rename: None, .. }
+                if !foreign_variants.is_empty() =>
+            {
+                foreign_variants.get(index).cloned().ok_or_else(|| {
+                    syn::Error::new_spanned(
+                        name,
+                        format!(
+                            "`variants` of `{}` has no variant at index {}",
+                            key, index
+                        ),
+                    )
+                })?
+            }
+            ReprVariant::Implicit { rename: None, .. } => {
+                rules.apply_member(name)
+            }
+            _ => continue,
+        };
+        let other_path = quote!(#to_path::#other).to_string().replace(' ', "");
+        let (value, message) = match pair_by {
+            PairBy::Discriminant => (
+                quote!(#from_path::#name as i128),
+                format!(
+                    "`{}` doesn't pair with `{}` by discriminant",
+                    name.to_token_stream(),
+                    other_path,
+                ),
+            ),
+            PairBy::Index => (
+                Literal::usize_unsuffixed(index).into_token_stream(),
+                format!(
+                    "`{}` doesn't pair with `{}` by index, or the \
+                    discriminant of `{1}` isn't its declaration index",
+                    name.to_token_stream(),
+                    other_path,
+                ),
+            ),
+        };
+        assertions.push(quote!(
+            assert!(#to_path::#other as i128 == #value, #message);
+        ));
+    }
+    Ok(quote!(
+        const _: () = {
+            #(#assertions)*
+        };
+    ))
+}
@@ -0,0 +1,111 @@
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use quote::{quote, ToTokens};
+use syn::{Expr, Member, Type};
+
+use super::AllVariantsOptions;
+use crate::{type_path, TypeRef};
+
+// The options of a variant that matter to a `by_discriminant` or `by_name`
+// key.
+pub(super) enum ReprVariant<'a> {
+    Implicit {
+        rename: Option<&'a Member>,
+        aliases: &'a [Member],
+    },
+    Value(&'a Expr),
+    Name(&'a Expr),
+    Skip,
+    Other,
+}
+
+pub(super) trait ReprOp {
+    fn repr(&self) -> ReprVariant<'_>;
+}
+
+impl<VO: ReprOp, FO> AllVariantsOptions<VO, FO> {
+    // Only `by_discriminant`, `by_name` and `pair_by` keys take a `value` or
+    // a `name`.
+    pub(super) fn check_values(&self, key: &Ident) -> syn::Result<()> {
+        for variant in &self.variants {
+            let (option, keys) = match variant.attrs.map_for(key).repr() {
+                ReprVariant::Value(_) => {
+                    ("value", "`by_discriminant` and `pair_by`")
+                }
+                ReprVariant::Name(_) => ("name", "`by_name`"),
+                _ => continue,
+            };
+            return Err(syn::Error::new_spanned(
+                &variant.name,
+                format!(
+                    "`{}` only applies to {} keys, `{}` isn't one",
+                    option, keys, key
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    // The variants along with their options for `key`, whose `option` needs
+    // a C-like enum.
+    pub(super) fn c_like(
+        &self,
+        key: &Ident,
+        option: &str,
+    ) -> syn::Result<Vec<(&Member, ReprVariant<'_>)>> {
+        self.variants
+            .iter()
+            .map(|variant| {
+                let name = &variant.name;
+                if variant.fields.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        name,
+                        format!(
+                            "`{}` needs a C-like enum, `{}` has fields",
+                            option,
+                            name.to_token_stream()
+                        ),
+                    ));
+                }
+                Ok((name, variant.attrs.map_for(key).repr()))
+            })
+            .collect()
+    }
+}
+
+pub(super) fn unexpected_options(
+    name: &Member,
+    key: &Ident,
+    option: &str,
+    expected: &str,
+) -> syn::Error {
+    syn::Error::new_spanned(
+        name,
+        format!(
+            "only {} apply to variants of `{}` key `{}`",
+            expected, option, key
+        ),
+    )
+}
+
+// `From<Self> for RAW`, given the value of each variant.
+pub(super) fn quote_from_self<'a>(
+    values: impl Iterator<Item = (&'a Member, TokenStream2)>,
+    type_ref: TypeRef,
+    subject: &Type,
+    raw: &Type,
+) -> TokenStream2 {
+    let generics = type_ref.generics();
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let subject_path = type_path(subject);
+    let lines =
+        values.map(|(name, value)| quote!(#subject_path::#name => #value,));
+    quote! {
+        impl #impl_generics std::convert::From<#subject> for #raw #where_clause {
+            fn from(value: #subject) -> Self {
+                match value {
+                    #(#lines)*
+                }
+            }
+        }
+    }
+}
@@ -1,46 +1,112 @@
+use std::iter::once;
+
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{DataEnum, Expr, Ident, Type};
+use syn::{DataEnum, Expr, Member, Path};
 
-use super::AllVariantsOptions;
-use crate::{FieldNamer, FieldOp, ParseAttrsError, TryFromAttrs, TypeRef};
+use super::{
+    by_name, discriminant, pair, quote_unmapped,
+    repr::{ReprOp, ReprVariant},
+    variant_pattern, AllVariantsOptions, VariantFields,
+};
+use crate::{
+    cant_alias, cant_rename, convert_struct::TryFromFieldOp, type_path,
+    FieldNamer, FieldOp, MapType, Repr, Subject, TryFromAttrs, TypeRef,
+};
 
 enum TryFromVariantOp {
-    Into { rename: Option<Ident> },
+    Into {
+        rename: Option<Member>,
+        aliases: Vec<Member>,
+    },
+    Map {
+        expr: Box<MapType>,
+        rename: Option<Member>,
+        aliases: Vec<Member>,
+    },
+    TryMap {
+        expr: Box<MapType>,
+        rename: Option<Member>,
+        aliases: Vec<Member>,
+    },
+    Error(Box<Expr>),
+    Value(Box<Expr>),
+    Name(Box<Expr>),
     Default,
     Skip,
 }
 
 impl Default for TryFromVariantOp {
     fn default() -> Self {
-        Self::Into { rename: None }
+        Self::Into {
+            rename: None,
+            aliases: vec![],
+        }
     }
 }
 
 impl FieldOp for TryFromVariantOp {
-    fn rename(
-        mut self,
-        rename_to: Option<Ident>,
-    ) -> Result<Self, ParseAttrsError> {
+    const NAME_VALUES: &'static [&'static str] =
+        &["map", "try_map", "error", "value", "name"];
+    const PATHS: &'static [&'static str] = &["default", "skip"];
+    const SKIP_FROM: Option<&'static str> = Some("skip");
+
+    fn rename(mut self, rename_to: Option<Member>) -> syn::Result<Self> {
         match &mut self {
-            Self::Into { rename } => {
+            Self::Into { rename, .. }
+            | Self::Map { rename, .. }
+            | Self::TryMap { rename, .. } => {
                 *rename = rename_to;
                 Ok(self)
             }
             _ if rename_to.is_none() => Ok(self),
-            _ => Err(ParseAttrsError::CantRename),
+            _ => Err(cant_rename(rename_to)),
         }
     }
 
-    fn from_key_expr(_key: &str, _expr: Expr) -> Result<Self, ParseAttrsError> {
-        Err(ParseAttrsError::UnsupportedNameValue)
+    fn aliases(
+        mut self,
+        aliases_to: Option<(Path, Vec<Member>)>,
+    ) -> syn::Result<Self> {
+        let (path, aliases_to) = match aliases_to {
+            Some(aliases_to) => aliases_to,
+            None => return Ok(self),
+        };
+        match &mut self {
+            Self::Into { aliases, .. }
+            | Self::Map { aliases, .. }
+            | Self::TryMap { aliases, .. } => {
+                *aliases = aliases_to;
+                Ok(self)
+            }
+            _ => Err(cant_alias(path)),
+        }
+    }
+
+    fn from_key_expr(key: &str, expr: Expr) -> Option<Self> {
+        Some(match key {
+            "value" => Self::Value(Box::new(expr)),
+            "name" => Self::Name(Box::new(expr)),
+            "map" => Self::Map {
+                expr: Box::new(expr),
+                rename: None,
+                aliases: vec![],
+            },
+            "try_map" => Self::TryMap {
+                expr: Box::new(expr),
+                rename: None,
+                aliases: vec![],
+            },
+            "error" => Self::Error(Box::new(expr)),
+            _ => return None,
+        })
     }
 
-    fn from_key(key: &str) -> Result<Self, ParseAttrsError> {
-        Ok(match key {
+    fn from_key(key: &str) -> Option<Self> {
+        Some(match key {
             "default" => Self::Default,
             "skip" => Self::Skip,
-            _ => return Err(ParseAttrsError::UnsupportedPath),
+            _ => return None,
         })
     }
 
@@ -48,53 +114,155 @@ impl FieldOp for TryFromVariantOp {
         &'a self,
         namer @ &mut FieldNamer { from, to, .. }: &mut FieldNamer<'a>,
     ) -> TokenStream2 {
-        let name = namer.name;
+        let (from, to) = (type_path(from), type_path(to));
         match self {
-            TryFromVariantOp::Into { rename } => {
+            TryFromVariantOp::Into { rename, aliases } => {
                 let (this, other) = namer.with(rename);
-                quote!(#from::#other => #to::#this,)
+                let names = once(other).chain(namer.aliases(aliases));
+                match namer.variant {
+                    Some(VariantFields { pattern, body }) => {
+                        let pattern =
+                            variant_pattern(&from, names, Some(pattern));
+                        quote!(
+                            #[allow(unused_mut)]
+                            #pattern => #to::#this #body,
+                        )
+                    }
+                    None => {
+                        let pattern = variant_pattern(&from, names, None);
+                        quote!(#pattern => #to::#this,)
+                    }
+                }
+            }
+            TryFromVariantOp::Map {
+                expr,
+                rename,
+                aliases,
+            } => {
+                let (_, other) = namer.with(rename);
+                let names = once(other).chain(namer.aliases(aliases));
+                let rest = namer.variant.map(|_| quote!({ .. }));
+                let pattern = variant_pattern(&from, names, rest.as_ref());
+                // the pattern binds nothing and leaves the value to the map
+                quote!(#pattern => (#expr)(value),)
+            }
+            TryFromVariantOp::TryMap {
+                expr,
+                rename,
+                aliases,
+            } => {
+                let (_, other) = namer.with(rename);
+                let names = once(other).chain(namer.aliases(aliases));
+                let rest = namer.variant.map(|_| quote!({ .. }));
+                let pattern = variant_pattern(&from, names, rest.as_ref());
+                quote!(#pattern => (#expr)(value)?,)
+            }
+            TryFromVariantOp::Error(expr) => {
+                let (_, other) = namer.with(None);
+                let rest = namer.variant.map(|_| quote!({ .. }));
+                quote!(#from::#other #rest => return Err(#expr),)
             }
             TryFromVariantOp::Default => {
-                let _ = namer.with(None);
-                quote!(#from::#name => #to::default(),)
+                let (_, other) = namer.with(None);
+                let rest = namer.variant.map(|_| quote!({ .. }));
+                quote!(#from::#other #rest => #to::default(),)
             }
-            TryFromVariantOp::Skip => {
+            // `value` and `name` are rejected on keys matching by name
+            TryFromVariantOp::Value(_)
+            | TryFromVariantOp::Name(_)
+            | TryFromVariantOp::Skip => {
                 quote!()
             }
         }
     }
 }
 
+impl ReprOp for TryFromVariantOp {
+    fn repr(&self) -> ReprVariant<'_> {
+        match self {
+            Self::Into { rename, aliases } => ReprVariant::Implicit {
+                rename: rename.as_ref(),
+                aliases,
+            },
+            Self::Value(expr) => ReprVariant::Value(expr),
+            Self::Name(expr) => ReprVariant::Name(expr),
+            Self::Skip => ReprVariant::Skip,
+            _ => ReprVariant::Other,
+        }
+    }
+}
+
 pub(super) fn derive_try_from_enum(
     TryFromAttrs { types, err_ty }: &TryFromAttrs,
-    subject: &Type,
+    subject: &Subject,
     data: &DataEnum,
     from_self: bool,
-) -> TokenStream2 {
+    repr_u128: bool,
+) -> syn::Result<TokenStream2> {
     let filter_path = if from_self {
         "try_from_self"
     } else {
         "try_from"
     };
+    let err_ty = err_ty.given()?;
     let variants =
-        AllVariantsOptions::<TryFromVariantOp>::parse(data, filter_path, types);
+        AllVariantsOptions::<TryFromVariantOp, TryFromFieldOp>::parse(
+            data,
+            filter_path,
+            types,
+        )?;
     types
         .iter_with(subject, from_self)
-        .map(|type_ref @ TypeRef { from, to, .. }| {
+        .map(|type_ref @ TypeRef { key, from, to, .. }| {
+            match type_ref.repr {
+                Some(Repr::Discriminant) => {
+                    return discriminant::derive_try_from(
+                        &variants, type_ref, err_ty, repr_u128,
+                    )
+                }
+                Some(Repr::Name) => {
+                    return by_name::derive_try_from(
+                        &variants, type_ref, err_ty,
+                    )
+                }
+                None => {}
+            }
+            let assertions = match type_ref.pair_by {
+                Some(pair_by) if !from_self => {
+                    return pair::derive_foreign_to_self(
+                        &variants,
+                        type_ref,
+                        pair_by,
+                        Some(err_ty),
+                    )
+                }
+                Some(pair_by) => {
+                    pair::quote_assertions(&variants, type_ref, pair_by)?
+                }
+                None => quote!(),
+            };
+            variants.check_values(key)?;
+            let generics = type_ref.generics();
+            let (impl_generics, _, where_clause) =
+                generics.split_for_impl();
             let (lines, foreign_fields) =
                 variants.lines_n_fields(from_self, type_ref);
-            quote! {
-                impl std::convert::TryFrom<#from> for #to {
+            let unmapped = quote_unmapped(type_ref.unmapped);
+            Ok(quote! {
+                impl #impl_generics std::convert::TryFrom<#from> for #to #where_clause {
                     type Error = #err_ty;
 
                     fn try_from(value: #from) -> Result<#to, Self::Error> {
                         #foreign_fields
                         Ok(match value {
                             #lines
+                            #unmapped
                         })
                     }
                 }
-            }
+
+                #assertions
+            })
         })
         .collect()
 }
@@ -1,24 +1,35 @@
-use proc_macro2::{Ident, TokenStream as TokenStream2};
+use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{DataStruct, Expr, Type};
+use syn::{DataStruct, Expr, Member, Path};
 
-use super::AllFieldsOptions;
+use super::{AllFieldsOptions, Construct};
 use crate::{
-    FieldNamer, FieldOp, FromAttrs, MapRef, MapType, ParseAttrsError, TypeRef,
+    cant_nest, cant_rename, needs_map, FieldNamer, FieldOp, FromAttrs, MapRef,
+    MapType, Subject, TypeRef,
 };
 
-enum FromFieldOp {
+pub(crate) enum FromFieldOp {
     Map {
         expr: MapType,
-        rename: Option<Ident>,
+        rename: Option<Member>,
         map_ref: MapRef,
     },
     New(MapType),
     Into {
-        rename: Option<Ident>,
+        rename: Option<Member>,
     },
     Default,
     Skip,
+    // the foreign fields listed by `flatten(...)`, if any
+    Flatten(Vec<Member>),
+    // a field read from, or written to, below a foreign field
+    Nested(Vec<Member>, Box<Self>),
+    // a `map` of several foreign fields
+    Sources(Vec<Member>, Box<Self>),
+    // a `map` returning a tuple of this and other fields
+    Split(Vec<Member>, Box<Self>),
+    // an expression of the converted fields
+    Compute(Expr),
 }
 
 impl Default for FromFieldOp {
@@ -27,23 +38,78 @@ impl Default for FromFieldOp {
     }
 }
 
+impl FromFieldOp {
+    // Whether the field is built by a `map`, maybe of a nested field.
+    fn is_map(&self) -> bool {
+        match self {
+            Self::Map { .. } => true,
+            Self::Nested(_, op) | Self::Sources(_, op) => op.is_map(),
+            _ => false,
+        }
+    }
+}
+
 impl FieldOp for FromFieldOp {
-    fn rename(
-        mut self,
-        rename_to: Option<Ident>,
-    ) -> Result<Self, ParseAttrsError> {
+    const NAME_VALUES: &'static [&'static str] =
+        &["map", "map_ref", "map_mut", "new", "compute"];
+    const PATHS: &'static [&'static str] = &["default", "skip", "flatten"];
+    const SKIP_FROM: Option<&'static str> = None;
+
+    fn rename(mut self, rename_to: Option<Member>) -> syn::Result<Self> {
         match &mut self {
             Self::Map { rename, .. } | Self::Into { rename } => {
                 *rename = rename_to;
                 Ok(self)
             }
             _ if rename_to.is_none() => Ok(self),
-            _ => Err(ParseAttrsError::CantRename),
+            _ => Err(cant_rename(rename_to)),
+        }
+    }
+
+    fn nested(self, nested: Option<(Path, Vec<Member>)>) -> syn::Result<Self> {
+        match nested {
+            Some((_, members))
+                if matches!(self, Self::Map { .. } | Self::Into { .. }) =>
+            {
+                Ok(Self::Nested(members, Box::new(self)))
+            }
+            Some((path, _)) => Err(cant_nest(path)),
+            None => Ok(self),
         }
     }
 
-    fn from_key_expr(key: &str, expr: Expr) -> Result<Self, ParseAttrsError> {
-        Ok(match key {
+    fn sources(
+        self,
+        sources: Option<(Path, Vec<Member>)>,
+    ) -> syn::Result<Self> {
+        match sources {
+            Some((_, sources)) if self.is_map() => {
+                Ok(Self::Sources(sources, Box::new(self)))
+            }
+            Some((path, _)) => Err(needs_map(path)),
+            None => Ok(self),
+        }
+    }
+
+    fn split(self, split: Option<(Path, Vec<Member>)>) -> syn::Result<Self> {
+        match split {
+            Some((_, split)) if self.is_map() => {
+                Ok(Self::Split(split, Box::new(self)))
+            }
+            Some((path, _)) => Err(needs_map(path)),
+            None => Ok(self),
+        }
+    }
+
+    fn splits(&self) -> &[Member] {
+        match self {
+            Self::Split(split, _) => split,
+            _ => &[],
+        }
+    }
+
+    fn from_key_expr(key: &str, expr: Expr) -> Option<Self> {
+        Some(match key {
             "map" => Self::Map {
                 expr,
                 rename: None,
@@ -60,20 +126,42 @@ impl FieldOp for FromFieldOp {
                 map_ref: MapRef::Mut,
             },
             "new" => Self::New(expr),
-            _ => return Err(ParseAttrsError::UnsupportedNameValue),
+            "compute" => Self::Compute(expr),
+            _ => return None,
         })
     }
 
-    fn from_key(key: &str) -> Result<Self, ParseAttrsError> {
-        Ok(match key {
+    fn from_key(key: &str) -> Option<Self> {
+        Some(match key {
             "default" => Self::Default,
             "skip" => Self::Skip,
-            _ => return Err(ParseAttrsError::UnsupportedPath),
+            "flatten" => Self::Flatten(vec![]),
+            _ => return None,
         })
     }
 
+    fn from_key_members(key: &str, members: Vec<Member>) -> Option<Self> {
+        match key {
+            "flatten" => Some(Self::Flatten(members)),
+            _ => None,
+        }
+    }
+
+    fn flattens(&self) -> bool {
+        matches!(self, Self::Flatten(_))
+    }
+
+    fn struct_only(&self) -> Option<&'static str> {
+        match self {
+            Self::Flatten(_) => Some("flatten"),
+            Self::Nested(..) => Some("path"),
+            Self::Split(..) => Some("split"),
+            Self::Compute(_) => Some("compute"),
+            _ => None,
+        }
+    }
+
     fn quote<'a>(&'a self, namer: &mut FieldNamer<'a>) -> TokenStream2 {
-        let name = namer.name;
         match self {
             FromFieldOp::Map {
                 expr,
@@ -81,55 +169,101 @@ impl FieldOp for FromFieldOp {
                 map_ref,
             } => {
                 let (this, other) = namer.with(rename);
-                quote!(#this: (#expr)(#map_ref value.#other),)
+                let args = namer.args(other, map_ref);
+                namer.line(this, quote!((#expr)(#args)));
             }
             FromFieldOp::New(expr) => {
-                quote!(#name: (#expr)(),)
+                let name = namer.target();
+                namer.line(name, quote!((#expr)()));
             }
             FromFieldOp::Into { rename } => {
                 let (this, other) = namer.with(rename);
-                quote!(#this: value.#other.into(),)
+                let other = namer.source(other);
+                namer.line(this, quote!(#other.into()));
             }
+            // the struct update syntax of `fill_default` covers the field
+            FromFieldOp::Default if namer.fill => {}
             FromFieldOp::Default => {
-                quote!(#name: Default::default(),)
+                let name = namer.target();
+                namer.line(name, quote!(Default::default()));
             }
             FromFieldOp::Skip => {
                 let _ = namer.with(None);
-                quote!()
+            }
+            // evaluated once the other fields are converted and bound to
+            // locals of their names
+            FromFieldOp::Compute(expr) => {
+                let name = namer.target();
+                namer.computed.push((name, quote!(#expr)));
+            }
+            FromFieldOp::Nested(members, op) => {
+                let (first, nested) =
+                    members.split_first().expect("a non-empty `path`");
+                namer.foreign_name = first;
+                namer.nested = nested;
+                return op.quote(namer);
+            }
+            FromFieldOp::Sources(sources, op) => {
+                namer.sources = sources;
+                return op.quote(namer);
+            }
+            FromFieldOp::Split(split, op) => {
+                namer.split = split;
+                return op.quote(namer);
+            }
+            // the nested struct converts from the whole foreign one, or into
+            // one whose fields are spread into the foreign struct, or set the
+            // ones listed by `flatten(...)`
+            FromFieldOp::Flatten(fields) if namer.from_self => {
+                let name = namer.name;
+                if fields.is_empty() {
+                    return quote!(..value.#name.into());
+                }
+                namer.spread(fields, quote!(value.#name.into()));
+            }
+            FromFieldOp::Flatten(fields) => {
+                let name = namer.name;
+                namer.foreign_fields.extend(fields);
+                namer.line(name, quote!(value.clone().into()));
             }
         }
+        quote!()
     }
 }
 
 pub(super) fn derive_from_struct(
     container_attrs: &FromAttrs,
-    subject: &Type,
+    subject: &Subject,
     data: &DataStruct,
     from_self: bool,
-) -> TokenStream2 {
+) -> syn::Result<TokenStream2> {
     let filter_path = if from_self { "from_self" } else { "from" };
     let FromAttrs { types } = container_attrs;
     let fields = AllFieldsOptions::<FromFieldOp>::parse(
         &data.fields,
         filter_path,
         types,
-    );
+    )?;
 
     types
         .iter_with(subject, from_self)
         .map(|type_ref @ TypeRef { from, to, .. }| {
-            let (lines, foreign_fields) =
-                fields.lines_n_fields(from_self, type_ref);
-            quote! {
-                impl std::convert::From<#from> for #to {
+            let generics = type_ref.generics();
+            let (impl_generics, _, where_clause) =
+                generics.split_for_impl();
+            let Construct {
+                construct,
+                foreign_fields,
+                ..
+            } = fields.construct_n_fields(from_self, type_ref)?;
+            Ok(quote! {
+                impl #impl_generics std::convert::From<#from> for #to #where_clause {
                     fn from(value: #from) -> #to {
                         #foreign_fields
-                        Self {
-                            #lines
-                        }
+                        #construct
                     }
                 }
-            }
+            })
         })
         .collect()
 }
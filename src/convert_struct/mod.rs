@@ -1,29 +1,56 @@
 use proc_macro2::{Ident, TokenStream as TokenStream2};
-use quote::quote;
-use syn::{DataStruct, Fields, Type, TypePath};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::{
+    spanned::Spanned, DataStruct, Fields, Generics, Index, Member, Type,
+    Visibility,
+};
 
 use crate::{
-    parse_field_attrs, ContainerAttrs, FieldAttrs, FieldNamer, FieldOp,
-    TypeRef, Types,
+    binding_ident, convert_enum::VariantFields, parse_field_attrs, type_path,
+    ContainerAttrs, FieldAttrs, FieldNamer, FieldOp, Subject, TypeRef, Types,
 };
 
 mod from;
 mod try_from;
 
+pub(crate) use from::FromFieldOp;
+pub(crate) use try_from::TryFromFieldOp;
+
 pub(super) fn derive_convert_struct(
-    ContainerAttrs {
+    container_attrs: &ContainerAttrs,
+    vis: &Visibility,
+    subject: &Ident,
+    generics: &Generics,
+    data: &DataStruct,
+) -> syn::Result<TokenStream2> {
+    for (key, attr_type) in container_attrs.types().flat_map(|types| &types.0) {
+        if attr_type.unmapped.is_some()
+            || attr_type.non_exhaustive
+            || attr_type.repr.is_some()
+            || attr_type.pair_by.is_some()
+        {
+            return Err(syn::Error::new_spanned(
+                key,
+                "`error`, `fallback`, `non_exhaustive`, `by_discriminant`, \
+                `by_name` and `pair_by` only apply to enums",
+            ));
+        }
+        attr_type.rename.check_members(
+            data.fields.iter().filter_map(|f| f.ident.as_ref()),
+        )?;
+    }
+    let ContainerAttrs {
         from,
         from_self,
         try_from,
         try_from_self,
-    }: &ContainerAttrs,
-    subject: &Ident,
-    data: &DataStruct,
-) -> TokenStream2 {
-    let subject = Type::Path(TypePath {
-        qself: None,
-        path: subject.clone().into(),
-    });
+    } = container_attrs;
+    let error_enums = try_from::error_enums(
+        subject,
+        try_from.as_ref(),
+        try_from_self.as_ref(),
+    )?;
+    let subject = Subject::new(subject, generics);
     [
         from.as_ref().map(|attrs| {
             from::derive_from_struct(attrs, &subject, data, false)
@@ -32,10 +59,24 @@ pub(super) fn derive_convert_struct(
             .as_ref()
             .map(|attrs| from::derive_from_struct(attrs, &subject, data, true)),
         try_from.as_ref().map(|attrs| {
-            try_from::derive_try_from_struct(attrs, &subject, data, false)
+            try_from::derive_try_from_struct(
+                attrs,
+                vis,
+                &subject,
+                data,
+                false,
+                &error_enums,
+            )
         }),
         try_from_self.as_ref().map(|attrs| {
-            try_from::derive_try_from_struct(attrs, &subject, data, true)
+            try_from::derive_try_from_struct(
+                attrs,
+                vis,
+                &subject,
+                data,
+                true,
+                &error_enums,
+            )
         }),
     ]
     .into_iter()
@@ -43,80 +84,475 @@ pub(super) fn derive_convert_struct(
     .collect()
 }
 
-struct OneFieldOptions<'a, FO> {
-    name: &'a Ident,
+struct OneFieldOptions<FO> {
+    name: Member,
+    ty: Type,
     attrs: FieldAttrs<FO>,
 }
 
-struct AllFieldsOptions<'a, FO> {
-    fields: Vec<OneFieldOptions<'a, FO>>,
+pub(crate) struct AllFieldsOptions<FO> {
+    fields: Vec<OneFieldOptions<FO>>,
 }
 
-impl<'a, FO: FieldOp> AllFieldsOptions<'a, FO> {
-    fn parse(fields: &'a Fields, filter_path: &str, types: &Types) -> Self {
-        let fields = match fields {
-            Fields::Named(fields) => &fields.named,
-            _ => unimplemented!("Only structs with named fields are supported"),
-        };
+impl<FO: FieldOp> AllFieldsOptions<FO> {
+    pub(crate) fn parse(
+        fields: &Fields,
+        filter_path: &str,
+        types: &Types,
+    ) -> syn::Result<Self> {
         let fields = fields
-            .into_iter()
-            .map(|field| {
-                let attrs = parse_field_attrs(&field.attrs, filter_path)
-                    .expect("Parse attributes to find field options");
-                attrs.check(types);
-                OneFieldOptions {
-                    name: field.ident.as_ref().unwrap(),
-                    attrs,
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let attrs =
+                    parse_field_attrs(&field.attrs, filter_path, types)?;
+                let name = match &field.ident {
+                    Some(ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(Index::from(index)),
+                };
+                let ty = field.ty.clone();
+                Ok(OneFieldOptions { name, ty, attrs })
+            })
+            .collect::<syn::Result<_>>()?;
+        Ok(AllFieldsOptions { fields })
+    }
+
+    // The fields of a variant have no struct of their own to convert from,
+    // spread into or assign to.
+    pub(crate) fn check_struct_only(&self) -> syn::Result<()> {
+        for field in &self.fields {
+            let attrs = &field.attrs;
+            let option = attrs
+                .map
+                .values()
+                .chain([&attrs.with])
+                .find_map(FO::struct_only);
+            if let Some(option) = option {
+                return Err(syn::Error::new_spanned(
+                    &field.name,
+                    format!(
+                        "`{}` only applies to the fields of structs",
+                        option
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn construct_n_fields(
+        &self,
+        from_self: bool,
+        type_ref: TypeRef,
+    ) -> syn::Result<Construct> {
+        let Lines {
+            entries,
+            mut foreign_fields,
+            flattened,
+            assignments,
+            mut prelude,
+            computed,
+            attempts,
+            error_fields,
+            ..
+        } = self.lines(from_self, type_ref, false);
+        if !attempts.is_empty() {
+            // every field is tried before the first error is returned
+            prelude.insert(
+                0,
+                quote!(
+                    let mut __errors = Vec::new();
+                    #(#attempts)*
+                    if !__errors.is_empty() {
+                        return Err(__errors.into_iter().collect());
+                    }
+                ),
+            );
+        }
+        let mut lines = if computed.is_empty() {
+            quote_entries(&entries)
+        } else {
+            // the converted fields are bound to locals first, for the
+            // expressions of `compute` to read, typed as the fields of `Self`
+            // unless that's the foreign struct
+            let ty = |name: &Member| {
+                self.fields
+                    .iter()
+                    .find(|field| field.name == *name)
+                    .filter(|_| !from_self)
+                    .map(|field| {
+                        let ty = &field.ty;
+                        quote!(: #ty)
+                    })
+            };
+            let locals: Vec<_> =
+                entries.iter().map(|(name, _)| local_ident(name)).collect();
+            let names = entries.iter().map(|(name, _)| name);
+            let types = entries.iter().map(|(name, _)| ty(name));
+            let computed_locals: Vec<_> =
+                computed.iter().map(|(name, _)| local_ident(name)).collect();
+            let computed_names = computed.iter().map(|(name, _)| name);
+            let computed_types = computed.iter().map(|(name, _)| ty(name));
+            let values = entries.iter().map(|(_, value)| value);
+            let exprs = computed.iter().map(|(_, expr)| expr);
+            prelude.push(quote!(
+                #(let #locals #types = #values;)*
+                #(let #computed_locals #computed_types = #exprs;)*
+            ));
+            quote!(
+                #(#names: #locals,)*
+                #(#computed_names: #computed_locals,)*
+            )
+        };
+        lines.extend(
+            type_ref
+                .extra
+                .iter()
+                .map(|(name, expr)| quote!(#name: #expr,)),
+        );
+        let rest = if from_self {
+            // the fields of a flattened one are spread with struct update
+            // syntax, which takes a single base, unless `flatten(...)` lists
+            // them
+            let spreads: Vec<_> = flattened
+                .iter()
+                .filter(|(_, spread)| !spread.is_empty())
+                .collect();
+            match (spreads.as_slice(), type_ref.fill) {
+                ([], fill) => fill.map(ToTokens::into_token_stream),
+                ([(_, spread)], None) => Some(spread.clone()),
+                ([(name, _)], Some(_)) | ([_, (name, _), ..], _) => {
+                    return Err(syn::Error::new_spanned(
+                        name,
+                        format!(
+                            "key `{}` spreads a single `flatten` field \
+                            into the foreign struct, without `fill_default`, \
+                            the others list the foreign fields they set, \
+                            like `flatten(x, y)`",
+                            type_ref.key
+                        ),
+                    ));
                 }
+            }
+        } else {
+            type_ref.fill.map(ToTokens::into_token_stream)
+        };
+        // foreign fields with nested ones set by `path` start out as their
+        // default, unless the rest of the struct provides them
+        let mut roots = vec![];
+        for (root, _) in &assignments {
+            if rest.is_none() && !roots.contains(&root) {
+                roots.push(root);
+            }
+        }
+        lines.extend(
+            roots.iter().map(|root| quote!(#root: Default::default(),)),
+        );
+        lines.extend(rest);
+        let construct = if assignments.is_empty() && prelude.is_empty() {
+            quote!(Self { #lines })
+        } else if assignments.is_empty() {
+            quote!({
+                #(#prelude)*
+                Self { #lines }
             })
-            .collect();
-        AllFieldsOptions { fields }
+        } else {
+            let assignments = assignments.iter().map(|(_, line)| line);
+            quote!({
+                #(#prelude)*
+                let mut __target = Self { #lines };
+                #(#assignments)*
+                __target
+            })
+        };
+        foreign_fields.extend(type_ref.ignores.iter().cloned());
+        let rest_check = (type_ref.warn_rest && flattened.is_empty())
+            .then(|| quote_rest_check(type_ref.from, &foreign_fields));
+        let foreign_fields = quote_foreign_fields(
+            type_ref.from,
+            &foreign_fields,
+            type_ref.ignore_rest || !flattened.is_empty(),
+        );
+        let foreign_fields = quote!(#rest_check #foreign_fields);
+        Ok(Construct {
+            construct,
+            foreign_fields,
+            error_fields,
+        })
+    }
+
+    pub(crate) fn variant_fields(
+        &self,
+        from_self: bool,
+        type_ref: TypeRef,
+    ) -> VariantFields {
+        let Lines {
+            entries, bindings, ..
+        } = self.lines(from_self, type_ref, true);
+        let binding_idents = bindings.iter().map(binding_ident);
+        let lines = quote_entries(&entries);
+        // the pattern names every field, unless the key ignores the rest of
+        // the foreign ones
+        let rest = if from_self {
+            let skipped = self
+                .fields
+                .iter()
+                .map(|field| &field.name)
+                .filter(|name| !bindings.contains(name));
+            quote!(#(#skipped: _,)*)
+        } else if type_ref.ignore_rest {
+            quote!(..)
+        } else {
+            quote!()
+        };
+        VariantFields {
+            pattern: quote!({ #(
+                #bindings: mut #binding_idents,
+            )* #rest }),
+            body: quote!({ #lines }),
+        }
     }
 
-    fn lines_n_fields(
+    fn lines(
         &self,
         from_self: bool,
         TypeRef {
             key,
             from,
             to,
-            ignores,
+            rename,
+            fill,
+            context,
+            accumulate_errors,
+            error_enum,
+            ..
         }: TypeRef,
-    ) -> (TokenStream2, TokenStream2) {
-        let mut foreign_fields = ignores.to_owned();
-        let lines = self.fields.iter().map(|field| {
-            let name = field.name;
+        in_variant: bool,
+    ) -> Lines<'_> {
+        let mut foreign_fields = vec![];
+        let mut bindings = vec![];
+        let mut flattened = vec![];
+        let mut assignments = vec![];
+        let mut attempts = vec![];
+        let mut error_fields = vec![];
+        let mut flattened_attempts = vec![];
+        let mut prelude = vec![];
+        let mut entries = vec![];
+        let mut flattened_entries = vec![];
+        let mut computed = vec![];
+        // fields set by the `split` of another one
+        let split: Vec<_> = self
+            .fields
+            .iter()
+            .flat_map(|field| {
+                let splits = field.attrs.map_for(key).splits();
+                splits.iter().filter(move |name| **name != field.name)
+            })
+            .collect();
+        for field in &self.fields {
+            let name = &field.name;
+            if split.contains(&name) {
+                continue;
+            }
+            // the rules of the key rename the variants of enums, not their
+            // fields
+            let foreign_name = if in_variant {
+                name.clone()
+            } else {
+                rename.apply_member(name)
+            };
             let mut namer = FieldNamer {
                 from_self,
                 name,
-                foreign_field: None,
+                foreign_name: &foreign_name,
+                foreign_fields: vec![],
+                bindings: in_variant.then(Vec::new),
+                variant: None,
+                fill: fill.is_some(),
+                nested: &[],
+                assignments: vec![],
+                sources: &[],
+                split: &[],
+                prelude: vec![],
+                entries: vec![],
+                computed: vec![],
+                context,
+                accumulate: accumulate_errors,
+                attempts: vec![],
+                error_enum,
+                error_ty: None,
+                error_fields: vec![],
                 from,
                 to,
             };
-            let res = field.attrs.map_for(key).quote(&mut namer);
-            foreign_fields.extend(namer.foreign_field.into_iter().cloned());
-            res
-        });
-        let lines = quote!(
-            #(
-                #lines
-            )*
-        );
-        let foreign_fields = quote_foreign_fields(from, &foreign_fields);
-        (lines, foreign_fields)
+            let op = field.attrs.map_for(key);
+            let res = op.quote(&mut namer);
+            // several fields can be read below the same foreign one
+            for foreign_field in namer.foreign_fields {
+                if !foreign_fields.contains(foreign_field) {
+                    foreign_fields.push(foreign_field.clone());
+                }
+            }
+            prelude.extend(namer.prelude);
+            error_fields.extend(
+                namer
+                    .error_fields
+                    .into_iter()
+                    .map(|(name, ty)| (name.clone(), ty.clone())),
+            );
+            assignments.extend(
+                namer
+                    .assignments
+                    .into_iter()
+                    .map(|(root, line)| (root.clone(), line)),
+            );
+            for binding in namer.bindings.into_iter().flatten() {
+                if !bindings.contains(binding) {
+                    bindings.push(binding.clone());
+                }
+            }
+            let namer_entries = namer
+                .entries
+                .into_iter()
+                .map(|(name, value)| (name.clone(), value));
+            computed.extend(
+                namer
+                    .computed
+                    .into_iter()
+                    .map(|(name, expr)| (name.clone(), expr)),
+            );
+            if op.flattens() {
+                // flattened fields read the whole value, before the others
+                // move out of it
+                flattened_entries.extend(namer_entries);
+                flattened_attempts.extend(namer.attempts);
+                flattened.push((name, res));
+            } else {
+                entries.extend(namer_entries);
+                attempts.extend(namer.attempts);
+            }
+        }
+        flattened_entries.extend(entries);
+        flattened_attempts.extend(attempts);
+        Lines {
+            entries: flattened_entries,
+            computed,
+            attempts: flattened_attempts,
+            error_fields,
+            foreign_fields,
+            bindings,
+            flattened,
+            assignments,
+            prelude,
+        }
+    }
+}
+
+struct Lines<'a> {
+    // the fields of the struct being built and their values
+    entries: Vec<(Member, TokenStream2)>,
+    // the fields given by `compute` and their expressions
+    computed: Vec<(Member, TokenStream2)>,
+    foreign_fields: Vec<Member>,
+    bindings: Vec<Member>,
+    // the struct update syntax of `flatten` fields in `from_self`
+    flattened: Vec<(&'a Member, TokenStream2)>,
+    // the foreign fields assigned below once the foreign struct is built
+    assignments: Vec<(Member, TokenStream2)>,
+    // the `let` statements of `split` fields
+    prelude: Vec<TokenStream2>,
+    // the fallible fields tried with `accumulate_errors`
+    attempts: Vec<TokenStream2>,
+    // the fields with a variant in the error enum of `Error = auto`, and
+    // their error types
+    error_fields: Vec<(Member, Type)>,
+}
+
+struct Construct {
+    // the expression building the struct
+    construct: TokenStream2,
+    // the check that every foreign field is converted
+    foreign_fields: TokenStream2,
+    error_fields: Vec<(Member, Type)>,
+}
+
+fn quote_entries(entries: &[(Member, TokenStream2)]) -> TokenStream2 {
+    let entries = entries.iter().map(|(name, value)| quote!(#name: #value,));
+    quote!(#(#entries)*)
+}
+
+// The local a field is bound to before `compute` reads it, `_0`, `_1`...
+// for the fields of tuple structs.
+fn local_ident(member: &Member) -> Ident {
+    match member {
+        Member::Named(ident) => ident.clone(),
+        Member::Unnamed(index) => format_ident!("_{}", index),
     }
 }
 
-fn quote_foreign_fields(from: &Type, foreign_fields: &[Ident]) -> TokenStream2 {
+fn quote_foreign_fields(
+    from: &Type,
+    foreign_fields: &[Member],
+    ignore_rest: bool,
+) -> TokenStream2 {
     if foreign_fields.is_empty() {
         quote!()
     } else {
+        let from = type_path(from);
+        let rest = ignore_rest.then(|| quote!(..));
         quote!(
             {
                 let #from { #(
                     #foreign_fields: _,
-                )* } = &value;
+                )* #rest } = &value;
             }
         )
     }
 }
+
+// `ignore_rest(warn)` can't list the foreign fields, so it compares the size
+// of the foreign struct with the one of a struct of the fields it names, a
+// larger foreign struct has other fields and calls a deprecated function.
+// Zero-sized fields go unnoticed.
+fn quote_rest_check(from: &Type, foreign_fields: &[Member]) -> TokenStream2 {
+    let params: Vec<_> = (0..foreign_fields.len())
+        .map(|index| format_ident!("F{}", index))
+        .collect();
+    let note = format!(
+        "`{}` has fields that are neither converted nor ignored",
+        from.to_token_stream().to_string().replace(' ', "")
+    );
+    // spanned on the foreign type, as lints are silent in the code of macros
+    let check = quote_spanned! {from.span()=>
+        __Check::<{
+            std::mem::size_of::<#from>()
+                <= __size(#(|value: &#from| &value.#foreign_fields),*)
+        }>::check()
+    };
+    quote! {
+        const _: () = {
+            struct __Fields<#(#params),*>(#(#params),*);
+
+            const fn __size<T, #(#params),*>(
+                #(_: fn(&T) -> &#params),*
+            ) -> usize {
+                std::mem::size_of::<__Fields<#(#params),*>>()
+            }
+
+            struct __Check<const LISTED: bool>;
+
+            #[allow(dead_code)]
+            impl __Check<true> {
+                const fn check() {}
+            }
+
+            #[allow(dead_code)]
+            impl __Check<false> {
+                #[deprecated(note = #note)]
+                const fn check() {}
+            }
+
+            #check
+        };
+    }
+}
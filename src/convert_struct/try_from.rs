@@ -1,30 +1,47 @@
+use std::collections::HashMap;
+
 use proc_macro2::{Ident, TokenStream as TokenStream2};
-use quote::quote;
-use syn::{DataStruct, Expr, Type};
+use quote::{format_ident, quote};
+use syn::{
+    DataStruct, Expr, GenericArgument, Member, Path, PathArguments, ReturnType,
+    Type, Visibility,
+};
 
-use super::AllFieldsOptions;
+use super::{AllFieldsOptions, Construct};
 use crate::{
-    FieldNamer, FieldOp, MapRef, MapType, ParseAttrsError, TryFromAttrs,
-    TypeRef,
+    cant_nest, cant_rename, error_variant, member_name, needs_map, ErrorType,
+    FieldNamer, FieldOp, MapRef, MapType, Subject, TryFromAttrs, TypeRef,
 };
 
-enum TryFromFieldOp {
+pub(crate) enum TryFromFieldOp {
     Map {
         expr: MapType,
-        rename: Option<Ident>,
+        rename: Option<Member>,
         map_ref: MapRef,
     },
     TryMap {
         expr: MapType,
-        rename: Option<Ident>,
+        rename: Option<Member>,
         map_ref: MapRef,
     },
     New(MapType),
     TryInto {
-        rename: Option<Ident>,
+        rename: Option<Member>,
     },
     Default,
     Skip,
+    // the foreign fields listed by `flatten(...)`, if any
+    Flatten(Vec<Member>),
+    // a field read from, or written to, below a foreign field
+    Nested(Vec<Member>, Box<Self>),
+    // a `map` of several foreign fields
+    Sources(Vec<Member>, Box<Self>),
+    // a `map` returning a tuple of this and other fields
+    Split(Vec<Member>, Box<Self>),
+    // an expression of the converted fields
+    Compute(Expr),
+    // a field whose error type is given by `Error`
+    Error(Path, Box<Type>, Box<Self>),
 }
 
 impl Default for TryFromFieldOp {
@@ -33,11 +50,59 @@ impl Default for TryFromFieldOp {
     }
 }
 
+impl TryFromFieldOp {
+    // Whether the field is built by a `map`, maybe of a nested field.
+    fn is_map(&self) -> bool {
+        match self {
+            Self::Map { .. } | Self::TryMap { .. } => true,
+            Self::Nested(_, op) | Self::Sources(_, op) => op.is_map(),
+            _ => false,
+        }
+    }
+
+    // Whether converting the field can fail.
+    fn is_fallible(&self) -> bool {
+        match self {
+            Self::TryMap { .. } | Self::TryInto { .. } | Self::Flatten(_) => {
+                true
+            }
+            Self::Nested(_, op) | Self::Sources(_, op) | Self::Split(_, op) => {
+                op.is_fallible()
+            }
+            _ => false,
+        }
+    }
+
+    // A `try_map` whose error type `Error = auto` can't tell without an
+    // `Error` next to it.
+    fn untyped_try_map(&self) -> Option<&Expr> {
+        match self {
+            Self::TryMap { expr, .. } if closure_error(expr).is_none() => {
+                Some(expr)
+            }
+            Self::Nested(_, op) | Self::Sources(_, op) | Self::Split(_, op) => {
+                op.untyped_try_map()
+            }
+            _ => None,
+        }
+    }
+}
+
 impl FieldOp for TryFromFieldOp {
-    fn rename(
-        mut self,
-        rename_to: Option<Ident>,
-    ) -> Result<Self, ParseAttrsError> {
+    const NAME_VALUES: &'static [&'static str] = &[
+        "map",
+        "map_ref",
+        "map_mut",
+        "try_map",
+        "try_map_ref",
+        "try_map_mut",
+        "new",
+        "compute",
+    ];
+    const PATHS: &'static [&'static str] = &["default", "skip", "flatten"];
+    const SKIP_FROM: Option<&'static str> = None;
+
+    fn rename(mut self, rename_to: Option<Member>) -> syn::Result<Self> {
         match &mut self {
             Self::Map { rename, .. }
             | Self::TryMap { rename, .. }
@@ -46,12 +111,74 @@ impl FieldOp for TryFromFieldOp {
                 Ok(self)
             }
             _ if rename_to.is_none() => Ok(self),
-            _ => Err(ParseAttrsError::CantRename),
+            _ => Err(cant_rename(rename_to)),
+        }
+    }
+
+    fn nested(self, nested: Option<(Path, Vec<Member>)>) -> syn::Result<Self> {
+        match nested {
+            Some((_, members))
+                if matches!(
+                    self,
+                    Self::Map { .. }
+                        | Self::TryMap { .. }
+                        | Self::TryInto { .. }
+                ) =>
+            {
+                Ok(Self::Nested(members, Box::new(self)))
+            }
+            Some((path, _)) => Err(cant_nest(path)),
+            None => Ok(self),
+        }
+    }
+
+    fn sources(
+        self,
+        sources: Option<(Path, Vec<Member>)>,
+    ) -> syn::Result<Self> {
+        match sources {
+            Some((_, sources)) if self.is_map() => {
+                Ok(Self::Sources(sources, Box::new(self)))
+            }
+            Some((path, _)) => Err(needs_map(path)),
+            None => Ok(self),
+        }
+    }
+
+    fn split(self, split: Option<(Path, Vec<Member>)>) -> syn::Result<Self> {
+        match split {
+            Some((_, split)) if self.is_map() => {
+                Ok(Self::Split(split, Box::new(self)))
+            }
+            Some((path, _)) => Err(needs_map(path)),
+            None => Ok(self),
+        }
+    }
+
+    fn error(self, error: Option<(Path, Box<Type>)>) -> syn::Result<Self> {
+        match error {
+            Some((path, ty)) if self.is_fallible() => {
+                Ok(Self::Error(path, ty, Box::new(self)))
+            }
+            Some((path, _)) => Err(syn::Error::new_spanned(
+                path,
+                "`Error` only applies to the fields of `try_map`, `flatten` \
+                or `TryFrom`",
+            )),
+            None => Ok(self),
+        }
+    }
+
+    fn splits(&self) -> &[Member] {
+        match self {
+            Self::Split(split, _) => split,
+            Self::Error(_, _, op) => op.splits(),
+            _ => &[],
         }
     }
 
-    fn from_key_expr(key: &str, expr: Expr) -> Result<Self, ParseAttrsError> {
-        Ok(match key {
+    fn from_key_expr(key: &str, expr: Expr) -> Option<Self> {
+        Some(match key {
             "map" => Self::Map {
                 expr,
                 rename: None,
@@ -83,20 +210,47 @@ impl FieldOp for TryFromFieldOp {
                 map_ref: MapRef::Mut,
             },
             "new" => Self::New(expr),
-            _ => return Err(ParseAttrsError::UnsupportedNameValue),
+            "compute" => Self::Compute(expr),
+            _ => return None,
         })
     }
 
-    fn from_key(key: &str) -> Result<Self, ParseAttrsError> {
-        Ok(match key {
+    fn from_key(key: &str) -> Option<Self> {
+        Some(match key {
             "default" => Self::Default,
             "skip" => Self::Skip,
-            _ => return Err(ParseAttrsError::UnsupportedPath),
+            "flatten" => Self::Flatten(vec![]),
+            _ => return None,
         })
     }
 
+    fn from_key_members(key: &str, members: Vec<Member>) -> Option<Self> {
+        match key {
+            "flatten" => Some(Self::Flatten(members)),
+            _ => None,
+        }
+    }
+
+    fn flattens(&self) -> bool {
+        match self {
+            Self::Flatten(_) => true,
+            Self::Error(_, _, op) => op.flattens(),
+            _ => false,
+        }
+    }
+
+    fn struct_only(&self) -> Option<&'static str> {
+        match self {
+            Self::Error(..) => Some("Error"),
+            Self::Flatten(_) => Some("flatten"),
+            Self::Nested(..) => Some("path"),
+            Self::Split(..) => Some("split"),
+            Self::Compute(_) => Some("compute"),
+            _ => None,
+        }
+    }
+
     fn quote<'a>(&'a self, namer: &mut FieldNamer<'a>) -> TokenStream2 {
-        let name = namer.name;
         match self {
             TryFromFieldOp::Map {
                 expr,
@@ -104,7 +258,8 @@ impl FieldOp for TryFromFieldOp {
                 map_ref,
             } => {
                 let (this, other) = namer.with(rename);
-                quote!(#this: (#expr)(#map_ref value.#other),)
+                let args = namer.args(other, map_ref);
+                namer.line(this, quote!((#expr)(#args)));
             }
             TryFromFieldOp::TryMap {
                 expr,
@@ -112,32 +267,105 @@ impl FieldOp for TryFromFieldOp {
                 map_ref,
             } => {
                 let (this, other) = namer.with(rename);
-                quote!(#this: (#expr)(#map_ref value.#other)?,)
+                let args = namer.args(other, map_ref);
+                if namer.error_ty.is_none() {
+                    namer.error_ty = closure_error(expr);
+                }
+                let value =
+                    namer.try_value(this, other, quote!((#expr)(#args)));
+                namer.line(this, value);
             }
             TryFromFieldOp::New(expr) => {
-                quote!(#name: (#expr)(),)
+                let name = namer.target();
+                namer.line(name, quote!((#expr)()));
             }
             TryFromFieldOp::TryInto { rename } => {
                 let (this, other) = namer.with(rename);
-                quote!(#this: value.#other.try_into()?,)
+                let source = namer.source(other);
+                let value =
+                    namer.try_value(this, other, quote!(#source.try_into()));
+                namer.line(this, value);
             }
+            // the struct update syntax of `fill_default` covers the field
+            TryFromFieldOp::Default if namer.fill => {}
             TryFromFieldOp::Default => {
-                quote!(#name: Default::default(),)
+                let name = namer.target();
+                namer.line(name, quote!(Default::default()));
             }
             TryFromFieldOp::Skip => {
                 let _ = namer.with(None);
-                quote!()
+            }
+            // evaluated once the other fields are converted and bound to
+            // locals of their names
+            TryFromFieldOp::Compute(expr) => {
+                let name = namer.target();
+                namer.computed.push((name, quote!(#expr)));
+            }
+            TryFromFieldOp::Nested(members, op) => {
+                let (first, nested) =
+                    members.split_first().expect("a non-empty `path`");
+                namer.foreign_name = first;
+                namer.nested = nested;
+                return op.quote(namer);
+            }
+            TryFromFieldOp::Sources(sources, op) => {
+                namer.sources = sources;
+                return op.quote(namer);
+            }
+            TryFromFieldOp::Split(split, op) => {
+                namer.split = split;
+                return op.quote(namer);
+            }
+            TryFromFieldOp::Error(_, ty, op) => {
+                namer.error_ty = Some(ty);
+                return op.quote(namer);
+            }
+            // the nested struct converts from the whole foreign one, or into
+            // one whose fields are spread into the foreign struct, or set the
+            // ones listed by `flatten(...)`, with `accumulate_errors` its
+            // errors join the others
+            TryFromFieldOp::Flatten(fields) => {
+                let name = namer.name;
+                let value = if namer.from_self {
+                    quote!(value.#name.try_into())
+                } else {
+                    quote!(value.clone().try_into())
+                };
+                let value = if namer.accumulate {
+                    namer.attempt(name, value, quote!(__errors.extend(error)))
+                } else if let Some(context) = namer.context {
+                    // the field has no foreign name of its own, the nested
+                    // struct reads from or writes to the whole foreign one
+                    let field = member_name(name);
+                    quote!(#value.map_err(|error| {
+                        (#context)(error, #field, "")
+                    })?)
+                } else {
+                    namer.try_field(value)
+                };
+                if namer.from_self && fields.is_empty() {
+                    return quote!(..#value);
+                }
+                if namer.from_self {
+                    namer.spread(fields, value);
+                } else {
+                    namer.foreign_fields.extend(fields);
+                    namer.line(name, value);
+                }
             }
         }
+        quote!()
     }
 }
 
 pub(super) fn derive_try_from_struct(
     container_attrs: &TryFromAttrs,
-    subject: &Type,
+    vis: &Visibility,
+    subject: &Subject,
     data: &DataStruct,
     from_self: bool,
-) -> TokenStream2 {
+    error_enums: &ErrorEnums,
+) -> syn::Result<TokenStream2> {
     let filter_path = if from_self {
         "try_from_self"
     } else {
@@ -148,25 +376,209 @@ pub(super) fn derive_try_from_struct(
         &data.fields,
         filter_path,
         types,
-    );
+    )?;
 
     types
         .iter_with(subject, from_self)
-        .map(|type_ref @ TypeRef { from, to, .. }| {
-            let (lines, foreign_fields) =
-                fields.lines_n_fields(from_self, type_ref);
-            quote! {
-                impl std::convert::TryFrom<#from> for #to {
+        .map(|type_ref @ TypeRef { key, from, to, .. }| {
+            fields.check_errors(key, err_ty)?;
+            let generics = type_ref.generics();
+            let (impl_generics, _, where_clause) =
+                generics.split_for_impl();
+            let error_enum = error_enums.get(&(from_self, key.clone()));
+            let type_ref = TypeRef {
+                error_enum,
+                ..type_ref
+            };
+            let Construct {
+                construct,
+                foreign_fields,
+                error_fields,
+            } = fields.construct_n_fields(from_self, type_ref)?;
+            let err_ty = match (err_ty, error_enum) {
+                (ErrorType::Given(err_ty), _) => quote!(#err_ty),
+                (ErrorType::Auto(_), error_enum) => quote!(#error_enum),
+            };
+            let error_enum = error_enum.map(|error_enum| {
+                quote_error_enum(vis, error_enum, &error_fields)
+            });
+            Ok(quote! {
+                #error_enum
+
+                impl #impl_generics std::convert::TryFrom<#from> for #to #where_clause {
                     type Error = #err_ty;
 
                     fn try_from(value: #from) -> Result<#to, Self::Error> {
                         #foreign_fields
-                        Ok(Self {
-                            #lines
-                        })
+                        Ok(#construct)
                     }
                 }
-            }
+            })
         })
         .collect()
 }
+
+impl AllFieldsOptions<TryFromFieldOp> {
+    // The error types of `Error = auto` are given by the fields, or by the
+    // closures of their `try_map`.
+    fn check_errors(&self, key: &Ident, err_ty: &ErrorType) -> syn::Result<()> {
+        for field in &self.fields {
+            match (err_ty, field.attrs.map_for(key)) {
+                (ErrorType::Given(_), TryFromFieldOp::Error(path, ..)) => {
+                    return Err(syn::Error::new_spanned(
+                        path,
+                        "`Error` of a field only applies with `Error = auto`",
+                    ));
+                }
+                (ErrorType::Auto(_), op) => {
+                    if let Some(expr) = op.untyped_try_map() {
+                        return Err(syn::Error::new_spanned(
+                            expr,
+                            "`Error = auto` needs the error type of this \
+                            `try_map`, like `Error = \"Type\"` next to it",
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+// The enums of `Error = auto` by direction and key.
+pub(super) type ErrorEnums = HashMap<(bool, Ident), Ident>;
+
+// Names the enums of `Error = auto` after `Self` and the foreign type, like
+// `RectFromRect1Error`, or after the key when foreign types share a name.
+pub(super) fn error_enums(
+    subject: &Ident,
+    try_from: Option<&TryFromAttrs>,
+    try_from_self: Option<&TryFromAttrs>,
+) -> syn::Result<ErrorEnums> {
+    let mut names = vec![];
+    for (from_self, attrs) in [(false, try_from), (true, try_from_self)] {
+        let types = match attrs {
+            Some(TryFromAttrs {
+                types,
+                err_ty: ErrorType::Auto(_),
+            }) => types,
+            _ => continue,
+        };
+        for (key, attr_type) in &types.0 {
+            let foreign = type_name(&attr_type.ty).ok_or_else(|| {
+                syn::Error::new_spanned(
+                    &attr_type.ty,
+                    "`Error = auto` names its enum after the foreign type, \
+                    which needs a name",
+                )
+            })?;
+            let name = if from_self {
+                format_ident!("{}From{}Error", foreign, subject)
+            } else {
+                format_ident!("{}From{}Error", subject, foreign)
+            };
+            names.push((from_self, key, name));
+        }
+    }
+    Ok(names
+        .iter()
+        .map(|(from_self, key, name)| {
+            let shared =
+                names.iter().filter(|(_, _, other)| other == name).count() > 1;
+            let name = match (shared, from_self) {
+                (false, _) => name.clone(),
+                (true, true) => format_ident!("{}From{}Error", key, subject),
+                (true, false) => format_ident!("{}From{}Error", subject, key),
+            };
+            ((*from_self, (*key).clone()), name)
+        })
+        .collect())
+}
+
+fn type_name(ty: &Type) -> Option<&Ident> {
+    match ty {
+        Type::Path(type_path) => {
+            type_path.path.segments.last().map(|segment| &segment.ident)
+        }
+        Type::Reference(reference) => type_name(&reference.elem),
+        _ => None,
+    }
+}
+
+// The `E` of a closure returning `Result<T, E>`.
+fn closure_error(expr: &Expr) -> Option<&Type> {
+    let output = match expr {
+        Expr::Closure(closure) => &closure.output,
+        _ => return None,
+    };
+    let segment = match output {
+        ReturnType::Type(_, ty) => match &**ty {
+            Type::Path(type_path) => type_path.path.segments.last()?,
+            _ => return None,
+        },
+        ReturnType::Default => return None,
+    };
+    match &segment.arguments {
+        PathArguments::AngleBracketed(arguments)
+            if segment.ident == "Result" =>
+        {
+            match arguments.args.iter().nth(1)? {
+                GenericArgument::Type(error) => Some(error),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+// The error of `Error = auto`, with a variant wrapping the error of each
+// fallible field that has an error type, the others convert infallibly.
+fn quote_error_enum(
+    vis: &Visibility,
+    error_enum: &Ident,
+    error_fields: &[(Member, Type)],
+) -> TokenStream2 {
+    let variants: Vec<_> = error_fields
+        .iter()
+        .map(|(field, _)| error_variant(field))
+        .collect();
+    let types = error_fields.iter().map(|(_, ty)| ty);
+    let messages = error_fields.iter().map(|(field, _)| {
+        format!("failed to convert field `{}`: {{}}", member_name(field))
+    });
+    quote! {
+        #[derive(Debug)]
+        #vis enum #error_enum {
+            #(
+                #variants(#types),
+            )*
+        }
+
+        impl std::convert::From<std::convert::Infallible> for #error_enum {
+            fn from(error: std::convert::Infallible) -> Self {
+                match error {}
+            }
+        }
+
+        impl std::fmt::Display for #error_enum {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match *self {
+                    #(
+                        #error_enum::#variants(ref error) => write!(f, #messages, error),
+                    )*
+                }
+            }
+        }
+
+        impl std::error::Error for #error_enum {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                match *self {
+                    #(
+                        #error_enum::#variants(ref error) => Some(error),
+                    )*
+                }
+            }
+        }
+    }
+}
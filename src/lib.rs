@@ -1,64 +1,301 @@
 use std::collections::HashMap;
 
+use case::{RenameRule, RenameRules};
 use proc_macro::TokenStream;
-use proc_macro2::{Ident, TokenStream as TokenStream2};
-use quote::{quote, ToTokens};
+use proc_macro2::{
+    Group, Ident, Literal, TokenStream as TokenStream2, TokenTree,
+};
+use quote::{format_ident, quote, ToTokens};
 use syn::{
-    parse::Parse, parse_macro_input, Attribute, Data, DeriveInput, Expr, Lit,
-    Meta, MetaList, MetaNameValue, NestedMeta, Path, Type,
+    ext::IdentExt, parse::Parse, parse_macro_input, punctuated::Punctuated,
+    spanned::Spanned, Attribute, Data, DeriveInput, Expr, Generics, Index, Lit,
+    LitStr, Member, Meta, MetaList, MetaNameValue, NestedMeta, Path,
+    PathArguments, Token, Type, TypePath, WherePredicate,
 };
 
+mod case;
 mod convert_enum;
 mod convert_struct;
 
-#[proc_macro_derive(
-    Convert,
-    attributes(from, from_self, try_from, try_from_self)
+#[cfg_attr(
+    feature = "legacy-attrs",
+    proc_macro_derive(
+        Convert,
+        attributes(convert, from, from_self, try_from, try_from_self)
+    )
+)]
+#[cfg_attr(
+    not(feature = "legacy-attrs"),
+    proc_macro_derive(Convert, attributes(convert))
 )]
 pub fn derive_convert(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    if !input.generics.params.is_empty() {
-        unimplemented!("Generics are not supported!");
-    }
-    let container_attrs = parse_container_attrs(&input.attrs)
-        .expect("Parse attributes to find paths from `try_from`");
+    derive(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn derive(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let container_attrs = parse_container_attrs(&input.ident, &input.attrs)?;
+    check_common_keys(input, &container_attrs)?;
 
     match &input.data {
         Data::Struct(data) => convert_struct::derive_convert_struct(
             &container_attrs,
+            &input.vis,
             &input.ident,
+            &input.generics,
             data,
         ),
         Data::Enum(data) => convert_enum::derive_convert_enum(
             &container_attrs,
             &input.ident,
+            &input.generics,
+            &input.attrs,
             data,
         ),
-        Data::Union(_) => unimplemented!("Unions are not supported!"),
+        Data::Union(data) => Err(syn::Error::new_spanned(
+            data.union_token,
+            "`Convert` can't be derived for unions",
+        )),
     }
-    .into()
 }
 
 struct FieldNamer<'a> {
     from_self: bool,
-    name: &'a Ident,
-    foreign_field: Option<&'a Ident>,
+    name: &'a Member,
+    // `name` after the key's `rename_all`, `prefix` and `suffix`
+    foreign_name: &'a Member,
+    foreign_fields: Vec<&'a Member>,
+    bindings: Option<Vec<&'a Member>>,
+    variant: Option<&'a convert_enum::VariantFields>,
+    // the struct is completed with `fill_default`
+    fill: bool,
+    // the fields below `foreign_name` given by `path`
+    nested: &'a [Member],
+    // assignments of nested foreign fields, after the foreign struct is built
+    assignments: Vec<(&'a Member, TokenStream2)>,
+    // the foreign fields given by `from_fields`, read instead of one
+    sources: &'a [Member],
+    // the fields given by `split`, each set from an element of the tuple
+    split: &'a [Member],
+    // statements before the struct is built
+    prelude: Vec<TokenStream2>,
+    // the fields of the struct being built and their values
+    entries: Vec<(&'a Member, TokenStream2)>,
+    // the fields given by `compute`, set from the converted ones
+    computed: Vec<(&'a Member, TokenStream2)>,
+    context: Option<&'a Expr>,
+    // fallible fields are tried before the struct is built, see `attempt`
+    accumulate: bool,
+    attempts: Vec<TokenStream2>,
+    // the generated error enum, the error type of the field given by its
+    // `Error`, and the fields with a variant in the enum
+    error_enum: Option<&'a Ident>,
+    error_ty: Option<&'a Type>,
+    error_fields: Vec<(&'a Member, &'a Type)>,
     from: &'a Type,
     to: &'a Type,
 }
 impl<'a> FieldNamer<'a> {
-    fn with<I: Into<Option<&'a Ident>>>(
+    fn with<I: Into<Option<&'a Member>>>(
         &mut self,
         rename: I,
-    ) -> (&'a Ident, &'a Ident) {
+    ) -> (&'a Member, &'a Member) {
+        let other = rename.into().unwrap_or(self.foreign_name);
+        if self.from_self {
+            (other, self.name)
+        } else {
+            if self.sources.is_empty() {
+                self.foreign_fields.push(other);
+            }
+            (self.name, other)
+        }
+    }
+
+    // The name on the side being built, for fields not read from the other.
+    fn target(&self) -> &'a Member {
+        if self.from_self {
+            self.foreign_name
+        } else {
+            self.name
+        }
+    }
+
+    // Other foreign variants that convert into this one, `from_self` only
+    // produces the canonical variant.
+    fn aliases(&mut self, aliases: &'a [Member]) -> &'a [Member] {
         if self.from_self {
-            (rename.into().unwrap_or(self.name), self.name)
+            &[]
+        } else {
+            self.foreign_fields.extend(aliases);
+            aliases
+        }
+    }
+
+    fn source(&mut self, member: &'a Member) -> TokenStream2 {
+        let nested = if self.from_self { &[] } else { self.nested };
+        if let Some(bindings) = &mut self.bindings {
+            bindings.push(member);
+            let binding = binding_ident(member);
+            quote!(#binding #(.#nested)*)
+        } else {
+            quote!(value.#member #(.#nested)*)
+        }
+    }
+
+    // The arguments of a `map`, the fields of `from_fields` or else `member`.
+    fn args(&mut self, member: &'a Member, map_ref: &MapRef) -> TokenStream2 {
+        if self.sources.is_empty() {
+            let source = self.source(member);
+            return quote!(#map_ref #source);
+        }
+        let args: Vec<_> = self
+            .sources
+            .iter()
+            .map(|source| {
+                self.foreign_fields.push(source);
+                let source = self.source(source);
+                quote!(#map_ref #source)
+            })
+            .collect();
+        quote!(#(#args),*)
+    }
+
+    // `value?`, with the error wrapped by the `context` of the key in the
+    // names of the field and the foreign one, dotted down the `path` to the
+    // foreign field, or else paired with the name of the field, and
+    // collected with the other errors by `accumulate_errors`.
+    fn try_value(
+        &mut self,
+        this: &Member,
+        other: &Member,
+        value: TokenStream2,
+    ) -> TokenStream2 {
+        if self.context.is_none() && !self.accumulate {
+            return self.try_field(value);
+        }
+        let (foreign, field) = if self.from_self {
+            (this, other)
+        } else {
+            (other, this)
+        };
+        let field = member_name(field);
+        let foreign = if self.sources.is_empty() {
+            let nested = self.nested.iter().map(member_name);
+            let names: Vec<_> =
+                [member_name(foreign)].into_iter().chain(nested).collect();
+            names.join(".")
+        } else {
+            let names: Vec<_> = self.sources.iter().map(member_name).collect();
+            names.join(", ")
+        };
+        let error = match self.context {
+            Some(context) => quote!((#context)(error, #field, #foreign)),
+            None => quote!((#field, Into::into(error))),
+        };
+        if self.accumulate {
+            self.attempt(this, value, quote!(__errors.push(#error)))
         } else {
-            let rename = rename.into();
-            self.foreign_field = Some(rename.unwrap_or(self.name));
-            (self.name, rename.unwrap_or(self.name))
+            quote!(#value.map_err(|error| #error)?)
         }
     }
+
+    // `value?`, with the error wrapped in the variant of the field in the
+    // enum of `Error = auto`, fields without an `Error` convert infallibly.
+    fn try_field(&mut self, value: TokenStream2) -> TokenStream2 {
+        match (self.error_enum, self.error_ty) {
+            (Some(error_enum), Some(error_ty)) => {
+                let variant = error_variant(self.name);
+                self.error_fields.push((self.name, error_ty));
+                quote!(#value.map_err(#error_enum::#variant)?)
+            }
+            _ => quote!(#value?),
+        }
+    }
+
+    // Binds the result of `value` before the struct is built, `on_error`
+    // collects its `error`.
+    fn attempt(
+        &mut self,
+        member: &Member,
+        value: TokenStream2,
+        on_error: TokenStream2,
+    ) -> TokenStream2 {
+        let binding = prefixed_ident("__try", member);
+        self.attempts.push(quote!(
+            let #binding = match #value {
+                Ok(value) => Some(value),
+                Err(error) => {
+                    #on_error;
+                    None
+                }
+            };
+        ));
+        quote!(#binding.unwrap())
+    }
+
+    // Sets `member` of the struct being built, nested foreign fields are
+    // assigned once it's built instead.
+    fn line(&mut self, member: &'a Member, value: TokenStream2) {
+        if !self.split.is_empty() {
+            let binding = prefixed_ident("__split", member);
+            self.prelude.push(quote!(let #binding = #value;));
+            for (index, split) in self.split.iter().enumerate() {
+                let index = Index::from(index);
+                self.entries.push((split, quote!(#binding.#index)));
+            }
+            return;
+        }
+        match self.nested {
+            nested @ [_, ..] if self.from_self => {
+                self.assignments.push((
+                    member,
+                    quote!(__target.#member #(.#nested)* = #value;),
+                ));
+            }
+            _ => self.entries.push((member, value)),
+        }
+    }
+
+    // Binds the foreign struct a flattened field converts into, to set the
+    // foreign fields listed by its `flatten(...)` from.
+    fn spread(&mut self, fields: &'a [Member], value: TokenStream2) {
+        let binding = prefixed_ident("__flatten", self.name);
+        let to = self.to;
+        self.prelude.push(quote!(let #binding: #to = #value;));
+        for field in fields {
+            self.entries.push((field, quote!(#binding.#field)));
+        }
+    }
+}
+
+fn binding_ident(member: &Member) -> Ident {
+    prefixed_ident("__field", member)
+}
+
+fn error_variant(member: &Member) -> Ident {
+    match member {
+        Member::Named(ident) => {
+            let name = RenameRule::Pascal.apply(&ident.unraw().to_string());
+            Ident::new(&name, ident.span())
+        }
+        Member::Unnamed(index) => format_ident!("Field{}", index.index),
+    }
+}
+
+fn member_name(member: &Member) -> String {
+    match member {
+        Member::Named(ident) => ident.unraw().to_string(),
+        Member::Unnamed(index) => index.index.to_string(),
+    }
+}
+
+fn prefixed_ident(prefix: &str, member: &Member) -> Ident {
+    match member {
+        Member::Named(ident) => format_ident!("{}_{}", prefix, ident.unraw()),
+        Member::Unnamed(index) => format_ident!("{}_{}", prefix, index.index),
+    }
 }
 
 struct ContainerAttrs {
@@ -74,41 +311,220 @@ impl ContainerAttrs {
             && self.try_from.is_none()
             && self.try_from_self.is_none()
     }
+
+    fn types(&self) -> impl Iterator<Item = &Types> {
+        [
+            self.from.as_ref().map(|attrs| &attrs.types),
+            self.from_self.as_ref().map(|attrs| &attrs.types),
+            self.try_from.as_ref().map(|attrs| &attrs.types),
+            self.try_from_self.as_ref().map(|attrs| &attrs.types),
+        ]
+        .into_iter()
+        .flatten()
+    }
 }
 
+#[derive(Clone)]
 struct TryFromAttrs {
     types: Types,
-    err_ty: Type,
+    err_ty: ErrorType,
 }
 
+// The `Error` of `TryFrom` impls, given or generated for each impl by
+// `Error = auto`.
+#[derive(Clone)]
+enum ErrorType {
+    Given(Box<Type>),
+    Auto(Path),
+}
+
+impl ErrorType {
+    fn given(&self) -> syn::Result<&Type> {
+        match self {
+            ErrorType::Given(err_ty) => Ok(err_ty),
+            ErrorType::Auto(path) => Err(syn::Error::new_spanned(
+                path,
+                "`Error = auto` only applies to structs",
+            )),
+        }
+    }
+}
+
+#[derive(Clone)]
 struct FromAttrs {
     types: Types,
 }
 
+impl AsMut<Types> for TryFromAttrs {
+    fn as_mut(&mut self) -> &mut Types {
+        &mut self.types
+    }
+}
+
+impl AsMut<Types> for FromAttrs {
+    fn as_mut(&mut self) -> &mut Types {
+        &mut self.types
+    }
+}
+
+#[derive(Clone)]
 struct Types(HashMap<Ident, AttrType>);
 
+#[derive(Clone)]
 struct AttrType {
     ty: Type,
-    ignores: Vec<Ident>,
+    ignores: Vec<Member>,
+    ignore_rest: bool,
+    // `ignore_rest(warn)`, a warning rather than an error when the foreign
+    // struct has fields neither converted nor ignored.
+    warn_rest: bool,
+    bounds: Vec<WherePredicate>,
+    // Direction the key was declared in with `both`, its field options are
+    // shared by the two opposite impls.
+    both: Option<String>,
+    unmapped: Option<Unmapped>,
+    non_exhaustive: bool,
+    repr: Option<Repr>,
+    rename: RenameRules,
+    case_insensitive: bool,
+    pair_by: Option<PairBy>,
+    // The foreign variants in declaration order, given by `variants(...)` for
+    // `pair_by = "index"`.
+    foreign_variants: Vec<Member>,
+    fill: Option<Fill>,
+    // Foreign fields `Self` doesn't have, with the expressions building them.
+    extra: Vec<(Ident, Expr)>,
+    // Wraps the errors of fields with the names of the field and the foreign
+    // one.
+    context: Option<Box<Expr>>,
+    // Tries every field and returns all their errors.
+    accumulate_errors: bool,
+}
+
+// How variants pair with the ones of a foreign C-like enum, instead of by
+// name.
+#[derive(Clone, Copy)]
+enum PairBy {
+    Discriminant,
+    Index,
+}
+
+impl PairBy {
+    fn parse(lit: &Lit) -> syn::Result<Self> {
+        match lit {
+            Lit::Str(lit_str) if lit_str.value() == "discriminant" => {
+                Ok(PairBy::Discriminant)
+            }
+            Lit::Str(lit_str) if lit_str.value() == "index" => {
+                Ok(PairBy::Index)
+            }
+            _ => Err(syn::Error::new_spanned(
+                lit,
+                "expected `\"discriminant\"` or `\"index\"`",
+            )),
+        }
+    }
+}
+
+// What a C-like enum converts to and from instead of a foreign enum.
+#[derive(Clone, Copy, PartialEq)]
+enum Repr {
+    // the integer discriminants of its variants
+    Discriminant,
+    // the names of its variants
+    Name,
+}
+
+impl Repr {
+    fn option(self) -> &'static str {
+        match self {
+            Repr::Discriminant => "by_discriminant",
+            Repr::Name => "by_name",
+        }
+    }
+
+    fn from_path(path: &Path) -> Option<Self> {
+        if path_eq(path, "by_discriminant") {
+            Some(Repr::Discriminant)
+        } else if path_eq(path, "by_name") {
+            Some(Repr::Name)
+        } else {
+            None
+        }
+    }
+}
+
+// What foreign enum variants without a counterpart turn into.
+#[derive(Clone)]
+enum Unmapped {
+    Error(Box<Expr>),
+    Fallback(Ident),
+}
+
+// What fills the fields of a struct built without a line of their own.
+#[derive(Clone)]
+enum Fill {
+    Default,
+    Expr(Box<Expr>),
+}
+
+impl ToTokens for Fill {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        tokens.extend(match self {
+            Fill::Default => quote!(..Default::default()),
+            Fill::Expr(expr) => quote!(..(#expr)),
+        });
+    }
+}
+
+struct Subject<'a> {
+    ty: Type,
+    generics: &'a Generics,
+}
+
+impl<'a> Subject<'a> {
+    fn new(ident: &'a Ident, generics: &'a Generics) -> Self {
+        let (_, ty_generics, _) = generics.split_for_impl();
+        Self {
+            ty: syn::parse_quote!(#ident #ty_generics),
+            generics,
+        }
+    }
 }
 
 impl Types {
     fn iter_with<'a>(
         &'a self,
-        subject: &'a Type,
+        subject: &'a Subject<'a>,
         from_self: bool,
     ) -> impl Iterator<Item = TypeRef<'a>> {
         self.0.iter().map(move |(key, object)| {
             let (from, to) = if from_self {
-                (subject, &object.ty)
+                (&subject.ty, &object.ty)
             } else {
-                (&object.ty, subject)
+                (&object.ty, &subject.ty)
             };
             TypeRef {
                 key,
                 from,
                 to,
                 ignores: &object.ignores,
+                ignore_rest: object.ignore_rest,
+                warn_rest: object.warn_rest,
+                generics: subject.generics,
+                bounds: &object.bounds,
+                unmapped: object.unmapped.as_ref(),
+                non_exhaustive: object.non_exhaustive,
+                repr: object.repr,
+                rename: &object.rename,
+                case_insensitive: object.case_insensitive,
+                pair_by: object.pair_by,
+                foreign_variants: &object.foreign_variants,
+                fill: object.fill.as_ref(),
+                extra: &object.extra,
+                context: object.context.as_deref(),
+                accumulate_errors: object.accumulate_errors,
+                error_enum: None,
             }
         })
     }
@@ -119,190 +535,53 @@ struct TypeRef<'a> {
     key: &'a Ident,
     from: &'a Type,
     to: &'a Type,
-    ignores: &'a [Ident],
+    ignores: &'a [Member],
+    ignore_rest: bool,
+    warn_rest: bool,
+    generics: &'a Generics,
+    bounds: &'a [WherePredicate],
+    unmapped: Option<&'a Unmapped>,
+    non_exhaustive: bool,
+    repr: Option<Repr>,
+    rename: &'a RenameRules,
+    case_insensitive: bool,
+    pair_by: Option<PairBy>,
+    foreign_variants: &'a [Member],
+    fill: Option<&'a Fill>,
+    extra: &'a [(Ident, Expr)],
+    context: Option<&'a Expr>,
+    accumulate_errors: bool,
+    // the error enum generated by `Error = auto`
+    error_enum: Option<&'a Ident>,
 }
 
-fn parse_container_attrs(
-    attrs: &[Attribute],
-) -> Result<ContainerAttrs, ParseAttrsError> {
-    let attrs = ContainerAttrs {
-        from: parse_try_from_attrs(attrs, "from")?,
-        from_self: parse_try_from_attrs(attrs, "from_self")?,
-        try_from: parse_try_from_attrs(attrs, "try_from")?,
-        try_from_self: parse_try_from_attrs(attrs, "try_from_self")?,
-    };
-    if attrs.is_empty() {
-        Err(ParseAttrsError::NothingToImplement)
-    } else {
-        Ok(attrs)
+impl TypeRef<'_> {
+    fn generics(&self) -> Generics {
+        let mut generics = self.generics.clone();
+        if !self.bounds.is_empty() {
+            generics
+                .make_where_clause()
+                .predicates
+                .extend(self.bounds.iter().cloned());
+        }
+        generics
     }
 }
 
-struct MaybeFromAttrs {
-    types: Types,
-    err_ty: Option<Type>,
-}
-
-fn parse_try_from_attrs<T>(
-    attrs: &[Attribute],
-    filter_path: &str,
-) -> Result<Option<T>, ParseAttrsError>
-where
-    MaybeFromAttrs: TryInto<T, Error = ParseAttrsError>,
-{
-    let mut types = HashMap::new();
-    let mut err_ty = None;
-    let iter = attrs
-        .iter()
-        .filter(|attr| path_eq_convert(&attr.path, filter_path));
-
-    let mut attr_is_missing = true;
-
-    for attr in iter {
-        attr_is_missing = false;
-        let meta = attr.parse_meta().map_err(|_| ParseAttrsError::ParseMeta)?;
-        match meta {
-            Meta::List(list) => {
-                for nested in list.nested {
-                    match nested {
-                        NestedMeta::Meta(nested_meta) => match nested_meta {
-                            Meta::NameValue(name_value)
-                                if path_eq(&name_value.path, "Error") =>
-                            {
-                                let err: Type = lit_parse(&name_value.lit)
-                                    .ok_or(
-                                        ParseAttrsError::UnsupportedErrLiteral,
-                                    )?;
-                                if let Some(_old_err) = err_ty.replace(err) {
-                                    return Err(
-                                        ParseAttrsError::DuplicateAttributes,
-                                    );
-                                }
-                            }
-                            Meta::NameValue(name_value) => {
-                                let key = name_value
-                                    .path
-                                    .get_ident()
-                                    .cloned()
-                                    .ok_or(
-                                        ParseAttrsError::UnsupportedStructure,
-                                    )?;
-                                let map: Type = lit_parse(&name_value.lit)
-                                    .ok_or(
-                                        ParseAttrsError::UnsupportedKeyLiteral,
-                                    )?;
-                                if let Some(_old_value) = types.insert(
-                                    key,
-                                    AttrType {
-                                        ty: map,
-                                        ignores: vec![],
-                                    },
-                                ) {
-                                    return Err(
-                                        ParseAttrsError::DuplicateAttributes,
-                                    );
-                                }
-                            }
-                            Meta::List(list) => {
-                                let key =
-                                    list.path.get_ident().cloned().ok_or(
-                                        ParseAttrsError::UnsupportedStructure,
-                                    )?;
-                                let mut map: Option<Type> = None;
-                                let mut ignores = vec![];
-                                for meta in list.nested {
-                                    match meta {
-                                        NestedMeta::Lit(lit) => {
-                                            if map.replace(lit_parse(&lit)
-                                            .ok_or(ParseAttrsError::UnsupportedKeyLiteral)?).is_some() {
-                                                return Err(ParseAttrsError::DuplicateAttributes);
-                                            }
-                                        }
-                                        NestedMeta::Meta(meta) => {
-                                            match meta {
-                                                Meta::List(list) if path_eq(&list.path, "ignore") => {
-                                                    for nested in list.nested {
-                                                        match nested {
-                                                            NestedMeta::Lit(lit) => {
-                                                                let field: Ident = lit_parse(&lit)
-                                                                    .ok_or(ParseAttrsError::UnsupportedKeyLiteral)?;
-                                                                ignores.push(field);
-                                                            }
-                                                            _ => return Err(ParseAttrsError::UnsupportedStructure),
-                                                        }
-                                                    }
-                                                }
-                                                _ => return Err(ParseAttrsError::UnsupportedStructure),
-                                            }
-                                        }
-                                    }
-                                }
-                                match map {
-                                    None => {
-                                        return Err(ParseAttrsError::UnsupportedStructure);
-                                    }
-                                    Some(map) => {
-                                        if let Some(_old_value) = types.insert(
-                                            key,
-                                            AttrType { ty: map, ignores },
-                                        ) {
-                                            return Err(ParseAttrsError::DuplicateAttributes);
-                                        }
-                                    }
-                                }
-                            }
-                            _ => {
-                                return Err(
-                                    ParseAttrsError::UnsupportedStructure,
-                                );
-                            }
-                        },
-                        _ => return Err(ParseAttrsError::UnsupportedStructure),
-                    }
+fn type_path(ty: &Type) -> TokenStream2 {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) => {
+            let mut path = path.clone();
+            for segment in &mut path.segments {
+                if let PathArguments::AngleBracketed(args) =
+                    &mut segment.arguments
+                {
+                    args.colon2_token = Some(Default::default());
                 }
             }
-            _ => return Err(ParseAttrsError::UnsupportedStructure),
-        }
-    }
-    if attr_is_missing {
-        return Ok(None);
-    }
-    if types.is_empty() {
-        return Err(ParseAttrsError::NoPaths);
-    }
-    Ok(Some(
-        MaybeFromAttrs {
-            err_ty,
-            types: Types(types),
-        }
-        .try_into()?,
-    ))
-}
-
-impl TryFrom<MaybeFromAttrs> for TryFromAttrs {
-    type Error = ParseAttrsError;
-
-    fn try_from(
-        MaybeFromAttrs { err_ty, types }: MaybeFromAttrs,
-    ) -> Result<Self, Self::Error> {
-        if let Some(err_ty) = err_ty {
-            Ok(Self { types, err_ty })
-        } else {
-            Err(ParseAttrsError::NoErrType)
-        }
-    }
-}
-impl TryFrom<MaybeFromAttrs> for FromAttrs {
-    type Error = ParseAttrsError;
-
-    fn try_from(
-        MaybeFromAttrs { err_ty, types }: MaybeFromAttrs,
-    ) -> Result<Self, Self::Error> {
-        if let Some(_err_ty) = err_ty {
-            Err(ParseAttrsError::UnnecessaryErrType)
-        } else {
-            Ok(Self { types })
+            path.into_token_stream()
         }
+        _ => ty.into_token_stream(),
     }
 }
 
@@ -311,14 +590,6 @@ struct FieldAttrs<FO> {
     with: FO,
 }
 impl<FO> FieldAttrs<FO> {
-    fn check(&self, types: &Types) {
-        for key in self.map.keys() {
-            if !types.0.contains_key(key) {
-                panic!("There is no such 'try_from' key as {:?}", key);
-            }
-        }
-    }
-
     fn map_for(&self, key: &Ident) -> &FO {
         if let Some(map) = self.map.get(key) {
             map
@@ -328,6 +599,23 @@ impl<FO> FieldAttrs<FO> {
     }
 }
 
+fn check_key(key: &Ident, types: &Types, filter_path: &str) -> syn::Result<()> {
+    if types.0.contains_key(key) {
+        return Ok(());
+    }
+    let mut known: Vec<_> = types.0.keys().map(Ident::to_string).collect();
+    known.sort();
+    Err(syn::Error::new_spanned(
+        key,
+        format!(
+            "unknown `{}` key `{}`, expected {}",
+            filter_path,
+            key,
+            one_of(&known),
+        ),
+    ))
+}
+
 type MapType = Expr;
 
 enum MapRef {
@@ -347,239 +635,1656 @@ impl ToTokens for MapRef {
 }
 
 trait FieldOp: Sized + Default {
-    fn rename(self, rename_to: Option<Ident>) -> Result<Self, ParseAttrsError>;
+    const PATHS: &'static [&'static str];
 
-    fn from_key_expr(key: &str, expr: Expr) -> Result<Self, ParseAttrsError>;
+    const NAME_VALUES: &'static [&'static str];
 
-    fn from_key(key: &str) -> Result<Self, ParseAttrsError>;
+    // What a direction-agnostic `skip` turns into when building `Self`,
+    // if it can be built without the field at all.
+    const SKIP_FROM: Option<&'static str>;
 
-    fn quote<'a>(&'a self, namer: &mut FieldNamer<'a>) -> TokenStream2;
-}
+    fn rename(self, rename_to: Option<Member>) -> syn::Result<Self>;
 
-fn parse_field_attrs<FO: FieldOp>(
-    attrs: &[Attribute],
-    filter_path: &str,
-) -> Result<FieldAttrs<FO>, ParseAttrsError> {
-    let mut map = HashMap::new();
-    let mut with = None::<FO>;
-    let mut with_rename = None;
-    let iter = attrs
-        .iter()
-        .filter(|attr| path_eq_convert(&attr.path, filter_path));
-
-    for attr in iter {
-        let meta = attr.parse_meta().map_err(|_| ParseAttrsError::ParseMeta)?;
-        match meta {
-            Meta::List(list) => {
-                for nested in list.nested {
-                    match nested {
-                        NestedMeta::Meta(nested_meta) => {
-                            match nested_meta {
-                                Meta::NameValue(name_value) => {
-                                    if let Some(_old_with) = with.replace(
-                                        map_from_name_value(&name_value)?,
-                                    ) {
-                                        return Err(ParseAttrsError::DuplicateAttributes);
-                                    }
-                                }
-                                Meta::Path(path) => {
-                                    if let Some(_old_with) =
-                                        with.replace(map_from_path(&path)?)
-                                    {
-                                        return Err(ParseAttrsError::DuplicateAttributes);
-                                    }
-                                }
-                                Meta::List(list) => {
-                                    if let Some(key) =
-                                        list.path.get_ident().cloned()
-                                    {
-                                        if &key == "rename" {
-                                            let ident =
-                                                single_ident_from_meta_list(
-                                                    &list,
-                                                )?;
-                                            if let Some(_old_rename) =
-                                                with_rename
-                                                    .replace(ident.clone())
-                                            {
-                                                return Err(ParseAttrsError::DuplicateAttributes);
-                                            }
-                                        } else {
-                                            let with =
-                                                map_from_meta_list(&list)?;
-                                            if let Some(_old_value) =
-                                                map.insert(key, with)
-                                            {
-                                                return Err(ParseAttrsError::DuplicateAttributes);
-                                            }
-                                        }
-                                    } else {
-                                        return Err(ParseAttrsError::UnsupportedStructure);
-                                    }
-                                }
-                            }
-                        }
-                        _ => return Err(ParseAttrsError::UnsupportedStructure),
-                    }
-                }
-            }
-            _ => return Err(ParseAttrsError::UnsupportedStructure),
+    fn aliases(
+        self,
+        aliases: Option<(Path, Vec<Member>)>,
+    ) -> syn::Result<Self> {
+        match aliases {
+            Some((path, _)) => Err(unknown_option(&path, &[])),
+            None => Ok(self),
         }
     }
-    Ok(FieldAttrs {
-        map,
-        with: with.unwrap_or_default().rename(with_rename)?,
-    })
-}
 
-#[derive(Debug)]
-enum ParseAttrsError {
-    UnsupportedStructure,
-    ParseMeta,
-    NoPaths,
-    DuplicateAttributes,
-    NoErrType,
-    UnnecessaryErrType,
-    UnsupportedErrLiteral,
-    UnsupportedKeyLiteral,
-    UnsupportedExpressionLiteral,
-    NothingToImplement,
-    UnsupportedNameValue,
-    UnsupportedPath,
-    CantRename,
-}
-
-fn map_from_meta_list<FO: FieldOp>(
-    meta_list: &MetaList,
-) -> Result<FO, ParseAttrsError> {
-    let mut map = None::<FO>;
-    let mut rename = None;
-    for nested in &meta_list.nested {
+    fn nested(self, nested: Option<(Path, Vec<Member>)>) -> syn::Result<Self> {
         match nested {
-            NestedMeta::Meta(meta) => match kv_from_meta(meta)? {
-                KeyValue::Map(value) => {
-                    if let Some(_old_value) = map.replace(value) {
-                        return Err(ParseAttrsError::UnsupportedStructure);
-                    }
-                }
-                KeyValue::Rename(value) => {
-                    if let Some(_old_value) = rename.replace(value) {
-                        return Err(ParseAttrsError::DuplicateAttributes);
-                    }
-                }
-            },
-            _ => return Err(ParseAttrsError::UnsupportedStructure),
+            Some((path, _)) => Err(unknown_option(&path, &[])),
+            None => Ok(self),
         }
     }
-    map.unwrap_or_default().rename(rename)
-}
 
-enum KeyValue<FO> {
-    Rename(Ident),
-    Map(FO),
-}
-
-fn map_from_name_value<FO: FieldOp>(
-    name_value: &MetaNameValue,
-) -> Result<FO, ParseAttrsError> {
-    let ident = name_value
-        .path
-        .get_ident()
-        .ok_or(ParseAttrsError::UnsupportedStructure)?;
-    let expr: Expr = lit_parse(&name_value.lit)
-        .ok_or(ParseAttrsError::UnsupportedExpressionLiteral)?;
-    let key = ident.to_string();
-    FO::from_key_expr(&key, expr)
-}
+    fn sources(
+        self,
+        sources: Option<(Path, Vec<Member>)>,
+    ) -> syn::Result<Self> {
+        match sources {
+            Some((path, _)) => Err(unknown_option(&path, &[])),
+            None => Ok(self),
+        }
+    }
 
-fn map_from_path<FO: FieldOp>(path: &Path) -> Result<FO, ParseAttrsError> {
-    let ident = path
-        .get_ident()
-        .ok_or(ParseAttrsError::UnsupportedStructure)?;
-    let key = ident.to_string();
-    FO::from_key(&key)
-}
+    fn split(self, split: Option<(Path, Vec<Member>)>) -> syn::Result<Self> {
+        match split {
+            Some((path, _)) => Err(unknown_option(&path, &[])),
+            None => Ok(self),
+        }
+    }
 
-fn kv_from_meta<FO: FieldOp>(
-    meta: &Meta,
-) -> Result<KeyValue<FO>, ParseAttrsError> {
-    Ok(KeyValue::Map(match meta {
-        Meta::NameValue(name_value) => map_from_name_value(name_value)?,
-        Meta::Path(path) => map_from_path(path)?,
-        Meta::List(list) => {
-            if let Some(key) = list.path.get_ident().cloned() {
-                if &key == "rename" {
-                    let ident = single_ident_from_meta_list(list)?;
-                    return Ok(KeyValue::Rename(ident.clone()));
-                }
-            }
-            return Err(ParseAttrsError::UnsupportedStructure);
+    // The error type of the field, for its variant in the enum of
+    // `Error = auto`.
+    fn error(self, error: Option<(Path, Box<Type>)>) -> syn::Result<Self> {
+        match error {
+            Some((path, _)) => Err(unknown_option(&path, &[])),
+            None => Ok(self),
         }
-    }))
-}
+    }
 
-fn ident_from_meta(meta: &Meta) -> Result<&Ident, ParseAttrsError> {
-    match meta {
-        Meta::Path(path) => path
-            .get_ident()
-            .ok_or(ParseAttrsError::UnsupportedStructure),
-        _ => Err(ParseAttrsError::UnsupportedStructure),
+    // The other fields built by the `split` of this one.
+    fn splits(&self) -> &[Member] {
+        &[]
     }
-}
 
-fn path_eq(path: &Path, str: &str) -> bool {
-    path.get_ident()
-        .map(|ident| ident == str)
-        .unwrap_or_default()
-}
+    fn from_key_expr(key: &str, expr: Expr) -> Option<Self>;
 
-fn path_eq_convert(path: &Path, str: &str) -> bool {
-    path_eq(path, str)
-    /*
-    let mut iter = path.segments.iter();
-    matches!(
-        iter.next(),
-        Some(convert) if convert.arguments.is_none() && &convert.ident == "convert"
-    )
-    && matches!(
-        (iter.next(), iter.next()),
-        (Some(segment), None) if segment.arguments.is_none() && &segment.ident == str
-    )
-    */
-}
+    fn from_key(key: &str) -> Option<Self>;
 
-fn _single_meta_from_meta_list(
-    meta_list: &MetaList,
-) -> Result<&Meta, ParseAttrsError> {
-    if meta_list.nested.len() != 1 {
-        return Err(ParseAttrsError::UnsupportedStructure);
+    // `KEY(...)` options listing fields, like `flatten(x, y)`.
+    fn from_key_members(_key: &str, _members: Vec<Member>) -> Option<Self> {
+        None
     }
-    let nested_meta = meta_list.nested.iter().next().unwrap();
-    match nested_meta {
-        NestedMeta::Meta(nested_meta) => Ok(nested_meta),
-        _ => Err(ParseAttrsError::UnsupportedStructure),
+
+    // Whether the field is built from, or spread into, the whole other
+    // struct rather than one of its fields.
+    fn flattens(&self) -> bool {
+        false
     }
-}
 
-fn single_ident_from_meta_list(
-    meta_list: &MetaList,
-) -> Result<Ident, ParseAttrsError> {
-    if meta_list.nested.len() != 1 {
-        return Err(ParseAttrsError::UnsupportedStructure);
+    // The option that needs the field to belong to a struct, not a variant.
+    fn struct_only(&self) -> Option<&'static str> {
+        None
     }
-    let nested_meta = meta_list.nested.iter().next().unwrap();
-    Ok(match nested_meta {
-        NestedMeta::Meta(nested_meta) => ident_from_meta(nested_meta)?.clone(),
-        NestedMeta::Lit(lit) => {
-            lit_parse(lit).ok_or(ParseAttrsError::UnsupportedStructure)?
-        }
-    })
+
+    fn quote<'a>(&'a self, namer: &mut FieldNamer<'a>) -> TokenStream2;
 }
 
-fn lit_parse<T: Parse>(lit: &Lit) -> Option<T> {
-    if let Lit::Str(lit_str) = lit {
-        lit_str.parse().ok()
+fn parse_container_attrs(
+    ident: &Ident,
+    attrs: &[Attribute],
+) -> syn::Result<ContainerAttrs> {
+    if let Some(nested) = common_items(attrs)?.first() {
+        let message = format!("expected {}", one_of(DIRECTIONS));
+        return Err(match nested {
+            NestedMeta::Meta(meta) => unknown_option(meta.path(), DIRECTIONS),
+            NestedMeta::Lit(lit) => syn::Error::new_spanned(lit, message),
+        });
+    }
+    let mut attrs = ContainerAttrs {
+        from: parse_try_from_attrs(attrs, "from")?,
+        from_self: parse_try_from_attrs(attrs, "from_self")?,
+        try_from: parse_try_from_attrs(attrs, "try_from")?,
+        try_from_self: parse_try_from_attrs(attrs, "try_from_self")?,
+    };
+    mirror_keys(&attrs.from, &mut attrs.from_self, "from", "from_self")?;
+    mirror_keys(&attrs.from_self, &mut attrs.from, "from_self", "from")?;
+    mirror_keys(
+        &attrs.try_from,
+        &mut attrs.try_from_self,
+        "try_from",
+        "try_from_self",
+    )?;
+    mirror_keys(
+        &attrs.try_from_self,
+        &mut attrs.try_from,
+        "try_from_self",
+        "try_from",
+    )?;
+    if attrs.is_empty() {
+        Err(syn::Error::new_spanned(
+            ident,
+            "nothing to implement, expected `#[convert(...)]` with at least \
+            one of `from`, `from_self`, `try_from` or `try_from_self`",
+        ))
     } else {
-        None
+        Ok(attrs)
+    }
+}
+
+struct MaybeFromAttrs {
+    types: Types,
+    err_ty: Option<(Path, ErrorType)>,
+    path: Path,
+}
+
+fn parse_try_from_attrs<T>(
+    attrs: &[Attribute],
+    filter_path: &str,
+) -> syn::Result<Option<T>>
+where
+    MaybeFromAttrs: TryInto<T, Error = syn::Error>,
+{
+    let mut types = HashMap::new();
+    let mut err_ty = None;
+    let mut bounds = vec![];
+    let mut error = None::<Expr>;
+    let mut context = None::<Expr>;
+    let mut accumulate_errors = None;
+    let mut repr = None;
+    let mut attr_path = None;
+
+    for list in attr_lists(attrs, filter_path)? {
+        attr_path.get_or_insert_with(|| list.path.clone());
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if path_eq(&name_value.path, "Error") =>
+                {
+                    let err = match &name_value.lit {
+                        Lit::Str(lit_str) if lit_str.value() == "auto" => {
+                            ErrorType::Auto(name_value.path.clone())
+                        }
+                        lit => ErrorType::Given(lit_parse(lit)?),
+                    };
+                    if let Some(_old_err) =
+                        err_ty.replace((name_value.path.clone(), err))
+                    {
+                        return Err(duplicate(&name_value.path));
+                    }
+                }
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if path_eq(&name_value.path, "bound") =>
+                {
+                    bounds.extend(lit_parse_bounds(&name_value.lit)?);
+                }
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if filter_path.starts_with("try_from")
+                        && path_eq(&name_value.path, "error") =>
+                {
+                    let expr = lit_parse(&name_value.lit)?;
+                    if let Some(_old_error) = error.replace(expr) {
+                        return Err(duplicate(&name_value.path));
+                    }
+                }
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if filter_path.starts_with("try_from")
+                        && path_eq(&name_value.path, "context") =>
+                {
+                    let expr = lit_parse(&name_value.lit)?;
+                    if let Some(_old_context) = context.replace(expr) {
+                        return Err(duplicate(&name_value.path));
+                    }
+                }
+                NestedMeta::Meta(Meta::Path(path))
+                    if filter_path.starts_with("try_from")
+                        && path_eq(&path, "accumulate_errors") =>
+                {
+                    if let Some(_old_value) =
+                        accumulate_errors.replace(path.clone())
+                    {
+                        return Err(duplicate(&path));
+                    }
+                }
+                NestedMeta::Meta(Meta::Path(path))
+                    if Repr::from_path(&path).is_some_and(|repr| {
+                        key_options(filter_path).contains(&repr.option())
+                    }) =>
+                {
+                    if let Some(old_value) = repr.replace(path.clone()) {
+                        return Err(repr_conflict(&old_value, &path));
+                    }
+                }
+                NestedMeta::Meta(Meta::NameValue(name_value)) => {
+                    let key = key_ident(&name_value.path)?;
+                    let attr_type = AttrType {
+                        ty: lit_parse(&name_value.lit)?,
+                        ignores: vec![],
+                        ignore_rest: false,
+                        warn_rest: false,
+                        bounds: vec![],
+                        both: None,
+                        unmapped: None,
+                        non_exhaustive: false,
+                        repr: None,
+                        rename: RenameRules::default(),
+                        case_insensitive: false,
+                        pair_by: None,
+                        foreign_variants: vec![],
+                        fill: None,
+                        extra: vec![],
+                        context: None,
+                        accumulate_errors: false,
+                    };
+                    if let Some(_old_value) = types.insert(key, attr_type) {
+                        return Err(duplicate(&name_value.path));
+                    }
+                }
+                NestedMeta::Meta(Meta::List(list)) => {
+                    let key = key_ident(&list.path)?;
+                    let attr_type = parse_attr_type(&list, filter_path)?;
+                    if let Some(_old_value) = types.insert(key, attr_type) {
+                        return Err(duplicate(&list.path));
+                    }
+                }
+                nested => {
+                    return Err(syn::Error::new_spanned(
+                        nested,
+                        "expected `KEY = \"Type\"`, `KEY(\"Type\", ...)`, \
+                        `Error = \"Type\"` or `bound = \"...\"`",
+                    ));
+                }
+            }
+        }
+    }
+    let path = match attr_path {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+    if types.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &path,
+            format!(
+                "expected at least one key, like `#[convert({}(V1 = \"Type\"))]`",
+                filter_path
+            ),
+        ));
+    }
+    for (key, attr_type) in &mut types {
+        attr_type.bounds.extend(bounds.iter().cloned());
+        if let (None, Some(error)) = (&attr_type.unmapped, &error) {
+            attr_type.unmapped = Some(Unmapped::Error(Box::new(error.clone())));
+        }
+        if let (None, Some(context)) = (&attr_type.context, &context) {
+            attr_type.context = Some(Box::new(context.clone()));
+        }
+        attr_type.accumulate_errors |= accumulate_errors.is_some();
+        if let Some(path) = &repr {
+            set_repr(path, attr_type)?;
+        }
+        match attr_type.repr {
+            Some(repr)
+                if filter_path == "try_from"
+                    && attr_type.unmapped.is_none() =>
+            {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    format!(
+                        "`{}` needs `error` or `fallback` \
+                        for the values without a variant",
+                        repr.option()
+                    ),
+                ));
+            }
+            Some(Repr::Name) => {}
+            _ if attr_type.case_insensitive => {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    "`case_insensitive` needs `by_name`",
+                ));
+            }
+            _ => attr_type.rename.check_idents(key)?,
+        }
+        let foreign_to_self = matches!(filter_path, "from" | "try_from");
+        match (attr_type.pair_by, &attr_type.unmapped) {
+            (Some(_), _) if attr_type.repr.is_some() => {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    "`pair_by` can't be combined with \
+                    `by_discriminant` or `by_name`",
+                ));
+            }
+            (Some(_), None) if foreign_to_self => {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    format!(
+                        "`pair_by` needs {} for the values without a partner",
+                        if filter_path == "from" {
+                            "`fallback`"
+                        } else {
+                            "`error` or `fallback`"
+                        }
+                    ),
+                ));
+            }
+            (None, Some(_)) if filter_path == "from" => {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    "`fallback` only applies to `from` keys with `pair_by`",
+                ));
+            }
+            _ => {}
+        }
+    }
+    MaybeFromAttrs {
+        err_ty,
+        types: Types(types),
+        path,
+    }
+    .try_into()
+    .map(Some)
+}
+
+fn key_options(filter_path: &str) -> Vec<&'static str> {
+    let mut options = vec![
+        "ignore",
+        "bound",
+        "both",
+        "bidirectional",
+        "pair_by",
+        "rename_all",
+        "prefix",
+        "suffix",
+        "fill_default",
+    ];
+    if filter_path.starts_with("try_from") {
+        options.extend(["error", "context", "accumulate_errors"]);
+    }
+    if matches!(filter_path, "from" | "try_from") {
+        options.push("ignore_rest");
+    } else {
+        options.push("extra");
+    }
+    if matches!(filter_path, "from" | "try_from" | "try_from_self") {
+        options.push("fallback");
+    }
+    if filter_path == "try_from" {
+        options.push("non_exhaustive");
+    }
+    if matches!(filter_path, "try_from" | "from_self") {
+        options.extend(["by_discriminant", "by_name"]);
+    }
+    if filter_path == "try_from" {
+        options.push("case_insensitive");
+    }
+    if is_from_self(filter_path) {
+        options.push("variants");
+    }
+    options
+}
+
+fn parse_attr_type(
+    list: &MetaList,
+    filter_path: &str,
+) -> syn::Result<AttrType> {
+    let mut ty: Option<Type> = None;
+    let mut ignores = vec![];
+    let mut ignore_rest = None;
+    let mut warn_rest = false;
+    let mut bounds = vec![];
+    let mut both = None;
+    let mut unmapped = None;
+    let mut non_exhaustive = None;
+    let mut repr = None;
+    let mut rename = RenameRules::default();
+    let mut case_insensitive = None;
+    let mut pair_by = None;
+    let mut foreign_variants = None;
+    let mut fill = None;
+    let mut extra = vec![];
+    let mut context = None;
+    let mut accumulate_errors = None;
+    let options = key_options(filter_path);
+    for nested in &list.nested {
+        match nested {
+            NestedMeta::Meta(Meta::NameValue(name_value))
+                if (options.contains(&"error")
+                    && path_eq(&name_value.path, "error"))
+                    || (options.contains(&"fallback")
+                        && path_eq(&name_value.path, "fallback")) =>
+            {
+                let value = if path_eq(&name_value.path, "error") {
+                    Unmapped::Error(lit_parse(&name_value.lit)?)
+                } else {
+                    Unmapped::Fallback(lit_parse(&name_value.lit)?)
+                };
+                if let Some(_old_value) = unmapped.replace(value) {
+                    return Err(syn::Error::new_spanned(
+                        &name_value.path,
+                        "only one of `error` or `fallback` can be set",
+                    ));
+                }
+            }
+            NestedMeta::Meta(Meta::NameValue(name_value))
+                if options.contains(&"context")
+                    && path_eq(&name_value.path, "context") =>
+            {
+                let value = lit_parse(&name_value.lit)?;
+                if let Some(_old_value) = context.replace(value) {
+                    return Err(duplicate(&name_value.path));
+                }
+            }
+            NestedMeta::Meta(Meta::Path(path))
+                if options.contains(&"accumulate_errors")
+                    && path_eq(path, "accumulate_errors") =>
+            {
+                if let Some(_old_value) = accumulate_errors.replace(path) {
+                    return Err(duplicate(path));
+                }
+            }
+            NestedMeta::Meta(Meta::Path(path))
+                if options.contains(&"non_exhaustive")
+                    && path_eq(path, "non_exhaustive") =>
+            {
+                if let Some(_old_value) = non_exhaustive.replace(path) {
+                    return Err(duplicate(path));
+                }
+            }
+            NestedMeta::Meta(Meta::Path(path))
+                if Repr::from_path(path)
+                    .is_some_and(|repr| options.contains(&repr.option())) =>
+            {
+                if let Some(old_value) = repr.replace(path) {
+                    return Err(repr_conflict(old_value, path));
+                }
+            }
+            NestedMeta::Meta(Meta::Path(path))
+                if options.contains(&"ignore_rest")
+                    && path_eq(path, "ignore_rest") =>
+            {
+                if let Some(_old_value) = ignore_rest.replace(path) {
+                    return Err(duplicate(path));
+                }
+            }
+            NestedMeta::Meta(Meta::List(list))
+                if options.contains(&"ignore_rest")
+                    && path_eq(&list.path, "ignore_rest") =>
+            {
+                match list.nested.first() {
+                    Some(NestedMeta::Meta(Meta::Path(path)))
+                        if list.nested.len() == 1 && path_eq(path, "warn") =>
+                    {
+                        warn_rest = true;
+                    }
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            list,
+                            "expected `ignore_rest` or `ignore_rest(warn)`",
+                        ))
+                    }
+                }
+                if let Some(_old_value) = ignore_rest.replace(&list.path) {
+                    return Err(duplicate(&list.path));
+                }
+            }
+            NestedMeta::Meta(Meta::Path(path))
+                if options.contains(&"case_insensitive")
+                    && path_eq(path, "case_insensitive") =>
+            {
+                if let Some(_old_value) = case_insensitive.replace(path) {
+                    return Err(duplicate(path));
+                }
+            }
+            NestedMeta::Meta(Meta::NameValue(name_value))
+                if path_eq(&name_value.path, "rename_all") =>
+            {
+                let rule = RenameRule::parse(lit_str(&name_value.lit)?)?;
+                if let Some(_old_value) = rename.rename_all.replace(rule) {
+                    return Err(duplicate(&name_value.path));
+                }
+            }
+            NestedMeta::Meta(Meta::NameValue(name_value))
+                if path_eq(&name_value.path, "prefix")
+                    || path_eq(&name_value.path, "suffix") =>
+            {
+                let value = lit_str(&name_value.lit)?.value();
+                let old_value = if path_eq(&name_value.path, "prefix") {
+                    rename.prefix.replace(value)
+                } else {
+                    rename.suffix.replace(value)
+                };
+                if let Some(_old_value) = old_value {
+                    return Err(duplicate(&name_value.path));
+                }
+            }
+            NestedMeta::Meta(Meta::NameValue(name_value))
+                if path_eq(&name_value.path, "pair_by") =>
+            {
+                let value = PairBy::parse(&name_value.lit)?;
+                if let Some(_old_value) = pair_by.replace((name_value, value)) {
+                    return Err(duplicate(&name_value.path));
+                }
+            }
+            NestedMeta::Meta(Meta::List(list))
+                if options.contains(&"variants")
+                    && path_eq(&list.path, "variants") =>
+            {
+                let members = lits_from_meta_list(list)?
+                    .into_iter()
+                    .map(lit_parse)
+                    .collect::<syn::Result<_>>()?;
+                if let Some(_old_value) =
+                    foreign_variants.replace((list, members))
+                {
+                    return Err(duplicate(&list.path));
+                }
+            }
+            NestedMeta::Meta(Meta::Path(path))
+                if path_eq(path, "fill_default") =>
+            {
+                if let Some(_old_value) = fill.replace(Fill::Default) {
+                    return Err(duplicate(path));
+                }
+            }
+            NestedMeta::Meta(Meta::NameValue(name_value))
+                if path_eq(&name_value.path, "fill_default") =>
+            {
+                let value = Fill::Expr(lit_parse(&name_value.lit)?);
+                if let Some(_old_value) = fill.replace(value) {
+                    return Err(duplicate(&name_value.path));
+                }
+            }
+            NestedMeta::Meta(Meta::Path(path))
+                if path_eq(path, "both") || path_eq(path, "bidirectional") =>
+            {
+                if let Some(_old_both) = both.replace(path) {
+                    return Err(duplicate(path));
+                }
+            }
+            NestedMeta::Lit(lit) => {
+                if let Some(_old_ty) = ty.replace(lit_parse(lit)?) {
+                    return Err(duplicate(lit));
+                }
+            }
+            NestedMeta::Meta(Meta::List(list))
+                if path_eq(&list.path, "ignore") =>
+            {
+                for lit in lits_from_meta_list(list)? {
+                    ignores.push(lit_parse(lit)?);
+                }
+            }
+            NestedMeta::Meta(Meta::List(list))
+                if options.contains(&"extra")
+                    && path_eq(&list.path, "extra") =>
+            {
+                for nested in &list.nested {
+                    let (name, expr) = parse_extra(nested)?;
+                    if extra.iter().any(|(other, _)| *other == name) {
+                        return Err(duplicate(nested));
+                    }
+                    extra.push((name, expr));
+                }
+            }
+            NestedMeta::Meta(Meta::List(list))
+                if path_eq(&list.path, "bound") =>
+            {
+                for lit in lits_from_meta_list(list)? {
+                    bounds.extend(lit_parse_bounds(lit)?);
+                }
+            }
+            NestedMeta::Meta(meta) => {
+                return Err(unknown_option(meta.path(), &options));
+            }
+        }
+    }
+    let ty = ty.ok_or_else(|| {
+        syn::Error::new_spanned(
+            list,
+            format!(
+                "expected foreign type, like `{}(\"Type\", ...)`",
+                list.path.to_token_stream()
+            ),
+        )
+    })?;
+    if let (Some(both), false) = (both, ignores.is_empty()) {
+        return Err(syn::Error::new_spanned(
+            both,
+            format!(
+                "`{}` can't be combined with `ignore`, \
+                ignored fields can't be converted back",
+                both.to_token_stream()
+            ),
+        ));
+    }
+    let ignores_empty = ignores.is_empty();
+    let mut attr_type = AttrType {
+        ty,
+        ignores,
+        ignore_rest: ignore_rest.is_some(),
+        warn_rest,
+        bounds,
+        both: both.map(|_| filter_path.to_owned()),
+        unmapped,
+        non_exhaustive: non_exhaustive.is_some(),
+        repr: None,
+        rename,
+        case_insensitive: case_insensitive.is_some(),
+        pair_by: pair_by.map(|(_, pair_by)| pair_by),
+        foreign_variants: vec![],
+        fill,
+        extra,
+        context,
+        accumulate_errors: accumulate_errors.is_some(),
+    };
+    if let (Some((name_value, _)), false) = (pair_by, ignores_empty) {
+        return Err(syn::Error::new_spanned(
+            &name_value.path,
+            "`pair_by` can't be combined with `ignore`",
+        ));
+    }
+    if let Some((list, members)) = foreign_variants {
+        if !matches!(attr_type.pair_by, Some(PairBy::Index)) {
+            return Err(syn::Error::new_spanned(
+                &list.path,
+                "`variants` needs `pair_by = \"index\"`",
+            ));
+        }
+        attr_type.foreign_variants = members;
+    }
+    if let Some(path) = repr {
+        set_repr(path, &mut attr_type)?;
+    }
+    if let (Some(non_exhaustive), None) = (non_exhaustive, &attr_type.unmapped)
+    {
+        return Err(syn::Error::new_spanned(
+            non_exhaustive,
+            "`non_exhaustive` needs `error` or `fallback` \
+            for the variants it can't name",
+        ));
+    }
+    Ok(attr_type)
+}
+
+// `field = "expr"`, or `field(default)` for `Default::default()`.
+fn parse_extra(nested: &NestedMeta) -> syn::Result<(Ident, Expr)> {
+    match nested {
+        NestedMeta::Meta(Meta::NameValue(name_value)) => {
+            Ok((key_ident(&name_value.path)?, lit_parse(&name_value.lit)?))
+        }
+        NestedMeta::Meta(Meta::List(list))
+            if list.nested.len() == 1
+                && matches!(
+                    &list.nested[0],
+                    NestedMeta::Meta(Meta::Path(path)) if path_eq(path, "default")
+                ) =>
+        {
+            Ok((
+                key_ident(&list.path)?,
+                syn::parse_quote!(Default::default()),
+            ))
+        }
+        _ => Err(syn::Error::new_spanned(
+            nested,
+            "expected `field = \"expr\"` or `field(default)`",
+        )),
+    }
+}
+
+fn repr_conflict(old_value: &Path, path: &Path) -> syn::Error {
+    if Repr::from_path(old_value) == Repr::from_path(path) {
+        duplicate(path)
+    } else {
+        syn::Error::new_spanned(
+            path,
+            "only one of `by_discriminant` or `by_name` can be set",
+        )
+    }
+}
+
+fn set_repr(path: &Path, attr_type: &mut AttrType) -> syn::Result<()> {
+    let repr = Repr::from_path(path).expect("`by_discriminant` or `by_name`");
+    let conflict = if attr_type.both.is_some() {
+        "both"
+    } else if !attr_type.ignores.is_empty() {
+        "ignore"
+    } else if let Some(other) = attr_type.repr.filter(|other| *other != repr) {
+        other.option()
+    } else {
+        attr_type.repr = Some(repr);
+        return Ok(());
+    };
+    Err(syn::Error::new_spanned(
+        path,
+        format!("`{}` can't be combined with `{}`", repr.option(), conflict),
+    ))
+}
+
+// Copies `both` keys of one direction into the opposite one, creating it
+// when the container doesn't declare it.
+fn mirror_keys<A: Clone + AsMut<Types>>(
+    attrs: &Option<A>,
+    reverse: &mut Option<A>,
+    filter_path: &str,
+    reverse_path: &str,
+) -> syn::Result<()> {
+    let mut template = match attrs {
+        Some(attrs) => attrs.clone(),
+        None => return Ok(()),
+    };
+    let mirrored: Vec<_> = std::mem::take(&mut template.as_mut().0)
+        .into_iter()
+        .filter(|(_, attr_type)| attr_type.both.as_deref() == Some(filter_path))
+        .collect();
+    if mirrored.is_empty() {
+        return Ok(());
+    }
+    let reverse = reverse.get_or_insert(template).as_mut();
+    for (key, mut attr_type) in mirrored {
+        // a fallback names a variant of the type converted into
+        if let Some(Unmapped::Fallback(_)) = attr_type.unmapped {
+            attr_type.unmapped = None;
+        }
+        attr_type.non_exhaustive = false;
+        attr_type.ignore_rest = false;
+        attr_type.warn_rest = false;
+        attr_type.extra.clear();
+        // and so does a `fill_default` expression
+        if let Some(Fill::Expr(_)) = attr_type.fill {
+            attr_type.fill = None;
+        }
+        if reverse.0.contains_key(&key) {
+            return Err(syn::Error::new_spanned(
+                &key,
+                format!(
+                    "key `{}` is declared with `both` in `{}` \
+                    and again in `{}`",
+                    key, filter_path, reverse_path,
+                ),
+            ));
+        }
+        reverse.0.insert(key, attr_type);
+    }
+    Ok(())
+}
+
+impl TryFrom<MaybeFromAttrs> for TryFromAttrs {
+    type Error = syn::Error;
+
+    fn try_from(
+        MaybeFromAttrs {
+            err_ty,
+            types,
+            path,
+        }: MaybeFromAttrs,
+    ) -> Result<Self, Self::Error> {
+        if let Some((_, err_ty)) = err_ty {
+            // the generated enum has a variant per field, not per error
+            let combined = types.0.values().any(|attr_type| {
+                attr_type.context.is_some() || attr_type.accumulate_errors
+            });
+            if let (ErrorType::Auto(path), true) = (&err_ty, combined) {
+                return Err(syn::Error::new_spanned(
+                    path,
+                    "`Error = auto` can't be combined with \
+                    `context` or `accumulate_errors`",
+                ));
+            }
+            Ok(Self { types, err_ty })
+        } else {
+            Err(syn::Error::new_spanned(
+                &path,
+                format!(
+                    "`{}` requires an error type, like `Error = \"Type\"`",
+                    path.to_token_stream()
+                ),
+            ))
+        }
+    }
+}
+impl TryFrom<MaybeFromAttrs> for FromAttrs {
+    type Error = syn::Error;
+
+    fn try_from(
+        MaybeFromAttrs {
+            err_ty,
+            types,
+            path,
+        }: MaybeFromAttrs,
+    ) -> Result<Self, Self::Error> {
+        if let Some((err_path, _)) = err_ty {
+            Err(syn::Error::new_spanned(
+                err_path,
+                format!(
+                    "`{}` is infallible and doesn't take `Error`, \
+                    use `try_{0}` instead",
+                    path.to_token_stream()
+                ),
+            ))
+        } else {
+            Ok(Self { types })
+        }
+    }
+}
+
+fn parse_field_attrs<FO: FieldOp>(
+    attrs: &[Attribute],
+    filter_path: &str,
+    types: &Types,
+) -> syn::Result<FieldAttrs<FO>> {
+    let lists = attr_lists(attrs, filter_path)?;
+    let (with, mut map) = keyed_options(
+        lists.iter().flat_map(|list| &list.nested),
+        |key| {
+            check_key(key, types, filter_path)?;
+            Ok(types.0[key].both.as_deref() != Some(filter_path))
+        },
+        specific_options,
+    )?;
+
+    // options of `both` keys are written for the direction they were
+    // declared in and have to make sense for the opposite one too
+    let mut both_map = HashMap::new();
+    for (key, attr_type) in &types.0 {
+        if let Some(both) = &attr_type.both {
+            let lists = attr_lists(attrs, both)?;
+            let (with, mut map) = keyed_options(
+                lists.iter().flat_map(|list| &list.nested),
+                |other| Ok(other == key),
+                |metas| common_options(metas, filter_path, is_from_self(both)),
+            )?;
+            let options = map.remove(key).unwrap_or(with);
+            both_map.insert(key.clone(), options);
+        }
+    }
+
+    let common = common_items(attrs)?;
+    let (common_with, mut common_map) = keyed_options(
+        &common,
+        |key| Ok(types.0.contains_key(key)),
+        |metas| common_options(metas, filter_path, false),
+    )?;
+
+    let mut keys: Vec<Ident> = vec![];
+    for key in map.keys().chain(both_map.keys()).chain(common_map.keys()) {
+        if !keys.contains(key) {
+            keys.push(key.clone());
+        }
+    }
+    let map = keys
+        .into_iter()
+        .map(|key| {
+            let specific = map.remove(&key).unwrap_or_default();
+            let both = both_map.remove(&key).unwrap_or_default();
+            let common = common_map.remove(&key).unwrap_or_default();
+            let options = specific.or(both).or(common);
+            Ok((key, options.finish(filter_path)?))
+        })
+        .collect::<syn::Result<_>>()?;
+    Ok(FieldAttrs {
+        map,
+        with: with.or(common_with).finish(filter_path)?,
+    })
+}
+
+struct FieldOptions<FO> {
+    op: Option<FO>,
+    rename: Option<Member>,
+    nested: Option<(Path, Vec<Member>)>,
+    aliases: Option<(Path, Vec<Member>)>,
+    sources: Option<(Path, Vec<Member>)>,
+    split: Option<(Path, Vec<Member>)>,
+    error: Option<(Path, Box<Type>)>,
+    missing: Option<syn::Error>,
+}
+
+impl<FO> Default for FieldOptions<FO> {
+    fn default() -> Self {
+        Self {
+            op: None,
+            rename: None,
+            nested: None,
+            aliases: None,
+            sources: None,
+            split: None,
+            error: None,
+            missing: None,
+        }
+    }
+}
+
+impl<FO: FieldOp> FieldOptions<FO> {
+    fn push(&mut self, meta: &Meta) -> syn::Result<()> {
+        match kv_from_meta(meta)? {
+            KeyValue::Map(value) => {
+                if let Some(_old_value) = self.op.replace(value) {
+                    return Err(duplicate(meta));
+                }
+            }
+            KeyValue::Rename(value) => {
+                if let Some(_old_value) = self.rename.replace(value) {
+                    return Err(duplicate(meta));
+                }
+            }
+            KeyValue::Nested(path, members) => {
+                if let Some(_old_value) = self.nested.replace((path, members)) {
+                    return Err(duplicate(meta));
+                }
+            }
+            KeyValue::Aliases(path, aliases) => {
+                if let Some(_old_value) = self.aliases.replace((path, aliases))
+                {
+                    return Err(duplicate(meta));
+                }
+            }
+            KeyValue::Sources(path, sources) => {
+                if let Some(_old_value) = self.sources.replace((path, sources))
+                {
+                    return Err(duplicate(meta));
+                }
+            }
+            KeyValue::Split(path, split) => {
+                if let Some(_old_value) = self.split.replace((path, split)) {
+                    return Err(duplicate(meta));
+                }
+            }
+            KeyValue::Error(path, error) => {
+                if let Some(_old_value) = self.error.replace((path, error)) {
+                    return Err(duplicate(meta));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn or(self, common: Self) -> Self {
+        // `rename` and `path` name the other field alike
+        let (rename, nested) = if self.rename.is_some() || self.nested.is_some()
+        {
+            (self.rename, self.nested)
+        } else {
+            (common.rename, common.nested)
+        };
+        Self {
+            missing: if self.op.is_some() {
+                None
+            } else {
+                self.missing.or(common.missing)
+            },
+            op: self.op.or(common.op),
+            rename,
+            nested,
+            aliases: self.aliases.or(common.aliases),
+            sources: self.sources.or(common.sources),
+            split: self.split.or(common.split),
+            error: self.error.or(common.error),
+        }
+    }
+
+    fn finish(self, filter_path: &str) -> syn::Result<FO> {
+        if let (None, Some(missing)) = (&self.op, self.missing) {
+            return Err(missing);
+        }
+        if let (true, Some((path, _))) =
+            (is_from_self(filter_path), &self.aliases)
+        {
+            return Err(syn::Error::new_spanned(
+                path,
+                format!(
+                    "`aliases` only apply to `from` and `try_from`, \
+                    `{}` always produces the canonical variant",
+                    filter_path
+                ),
+            ));
+        }
+        if let (Some(_), Some((path, _))) = (&self.rename, &self.nested) {
+            return Err(syn::Error::new_spanned(
+                path,
+                "`path` can't be combined with `rename` or `index`",
+            ));
+        }
+        if let (Some((path, _)), true) = (
+            &self.sources,
+            self.rename.is_some() || self.nested.is_some(),
+        ) {
+            return Err(syn::Error::new_spanned(
+                path,
+                "`from_fields` can't be combined with `rename`, `index` or `path`",
+            ));
+        }
+        let combined = self.sources.as_ref().or(self.split.as_ref());
+        if let (true, Some((path, _))) = (is_from_self(filter_path), combined) {
+            return Err(syn::Error::new_spanned(
+                path,
+                format!(
+                    "`{}` only applies to `from` and `try_from`, \
+                    `{}` builds a single foreign field from each field",
+                    path.to_token_stream(),
+                    filter_path
+                ),
+            ));
+        }
+        self.op
+            .unwrap_or_default()
+            .rename(self.rename)?
+            .nested(self.nested)?
+            .sources(self.sources)?
+            .split(self.split)?
+            .error(self.error)?
+            .aliases(self.aliases)
+    }
+}
+
+type KeyedOptions<FO> = (FieldOptions<FO>, HashMap<Ident, FieldOptions<FO>>);
+
+// Splits options into per-key ones and the ones for every other key,
+// `filter` decides which keys this direction takes.
+fn keyed_options<'a, FO, K, F>(
+    nested: impl IntoIterator<Item = &'a NestedMeta>,
+    filter: K,
+    parse: F,
+) -> syn::Result<KeyedOptions<FO>>
+where
+    K: Fn(&Ident) -> syn::Result<bool>,
+    F: Fn(Vec<&'a Meta>) -> syn::Result<FieldOptions<FO>>,
+{
+    let mut map = HashMap::new();
+    let mut with = vec![];
+    for nested in nested {
+        match nested {
+            NestedMeta::Meta(Meta::List(list)) if is_key(list) => {
+                let key = key_ident(&list.path)?;
+                if !filter(&key)? {
+                    continue;
+                }
+                let metas = list
+                    .nested
+                    .iter()
+                    .map(|nested| match nested {
+                        NestedMeta::Meta(meta) => Ok(meta),
+                        NestedMeta::Lit(lit) => {
+                            Err(syn::Error::new_spanned(lit, "expected option"))
+                        }
+                    })
+                    .collect::<syn::Result<_>>()?;
+                if let Some(_old_value) = map.insert(key, parse(metas)?) {
+                    return Err(duplicate(&list.path));
+                }
+            }
+            NestedMeta::Meta(meta) => with.push(meta),
+            NestedMeta::Lit(lit) => {
+                return Err(syn::Error::new_spanned(
+                    lit,
+                    "expected option or `KEY(...)`",
+                ));
+            }
+        }
+    }
+    Ok((parse(with)?, map))
+}
+
+fn specific_options<FO: FieldOp>(
+    metas: Vec<&Meta>,
+) -> syn::Result<FieldOptions<FO>> {
+    let mut options = FieldOptions::default();
+    for meta in metas {
+        options.push(meta)?;
+    }
+    Ok(options)
+}
+
+const COMMON_OPTIONS: &[&str] = &[
+    "rename",
+    "index",
+    "path",
+    "aliases",
+    "from_fields",
+    "split",
+    "skip",
+    "default",
+    "new",
+    "compute",
+    "flatten",
+    "map",
+    "map_ref",
+    "map_mut",
+    "try_map",
+    "try_map_ref",
+    "try_map_mut",
+    "inverse",
+    "inverse_ref",
+    "inverse_mut",
+    "try_inverse",
+    "try_inverse_ref",
+    "try_inverse_mut",
+    "value",
+    "name",
+    "Error",
+];
+
+const MAPS: &[&str] = &[
+    "map",
+    "map_ref",
+    "map_mut",
+    "try_map",
+    "try_map_ref",
+    "try_map_mut",
+];
+
+// Translates direction-agnostic options into the ones `filter_path` takes:
+// `map*` apply to `from` and `try_from` (or to the self directions when
+// `map_from_self` is set), their `inverse*` counterparts to the opposite
+// ones, while `skip`, `default`, `new`, `from_fields` and `split` only
+// describe how `Self` is built, and `Error` only applies to the `TryFrom`
+// directions.
+fn common_options<FO: FieldOp>(
+    metas: Vec<&Meta>,
+    filter_path: &str,
+    map_from_self: bool,
+) -> syn::Result<FieldOptions<FO>> {
+    let from_self = is_from_self(filter_path);
+    let use_map = from_self == map_from_self;
+    let mut options = FieldOptions::default();
+    let mut skip = None;
+    let mut map = None;
+    let mut inverse = None;
+    for meta in metas {
+        let path = meta.path();
+        let name = path.get_ident().map(Ident::to_string).unwrap_or_default();
+        let translated = match (meta, name.as_str()) {
+            (Meta::List(_), "rename")
+            | (Meta::Path(_) | Meta::List(_), "flatten")
+            | (Meta::NameValue(_), "index" | "path" | "value" | "name") => {
+                Some(meta.clone())
+            }
+            (Meta::List(_), "aliases" | "from_fields" | "split") => {
+                (!from_self).then(|| meta.clone())
+            }
+            (Meta::Path(_), "skip") => {
+                if let Some(_old_skip) = skip.replace(path) {
+                    return Err(duplicate(meta));
+                }
+                None
+            }
+            (Meta::Path(_), "default")
+            | (Meta::NameValue(_), "new" | "compute") => {
+                (!from_self).then(|| meta.clone())
+            }
+            (Meta::NameValue(_), "Error") => {
+                filter_path.starts_with("try_").then(|| meta.clone())
+            }
+            (Meta::NameValue(_), name) if MAPS.contains(&name) => {
+                if let Some(_old_map) = map.replace(path) {
+                    return Err(duplicate(meta));
+                }
+                use_map.then(|| meta.clone())
+            }
+            (Meta::NameValue(name_value), name)
+                if MAPS.contains(&name.replace("inverse", "map").as_str()) =>
+            {
+                if let Some(_old_inverse) = inverse.replace(path) {
+                    return Err(duplicate(meta));
+                }
+                (!use_map).then(|| {
+                    let name = name.replace("inverse", "map");
+                    Meta::NameValue(MetaNameValue {
+                        path: renamed_path(path, &name),
+                        ..name_value.clone()
+                    })
+                })
+            }
+            _ => return Err(unknown_option(path, COMMON_OPTIONS)),
+        };
+        if let Some(meta) = translated {
+            options.push(&meta)?;
+        }
+    }
+    let missing = match (from_self, skip) {
+        (true, Some(path)) => {
+            options.push(&Meta::Path(renamed_path(path, "skip")))?;
+            None
+        }
+        (false, Some(path)) if options.op.is_none() => match FO::SKIP_FROM {
+            Some(name) => {
+                options.push(&Meta::Path(renamed_path(path, name)))?;
+                None
+            }
+            None => Some((path, "`default` or `new`")),
+        },
+        _ => None,
+    };
+    let missing = missing.or(match (use_map, map, inverse) {
+        (false, Some(path), None) => Some((path, "`inverse`")),
+        (true, None, Some(path)) => Some((path, "`map`")),
+        _ => None,
+    });
+    options.missing = missing.map(|(path, counterpart)| {
+        syn::Error::new_spanned(
+            path,
+            format!(
+                "`{}` needs {} as its counterpart for `{}`",
+                path.to_token_stream(),
+                counterpart,
+                filter_path,
+            ),
+        )
+    });
+    Ok(options)
+}
+
+fn is_from_self(filter_path: &str) -> bool {
+    matches!(filter_path, "from_self" | "try_from_self")
+}
+
+fn renamed_path(path: &Path, name: &str) -> Path {
+    Ident::new(name, path.span()).into()
+}
+enum KeyValue<FO> {
+    Rename(Member),
+    Nested(Path, Vec<Member>),
+    Sources(Path, Vec<Member>),
+    Split(Path, Vec<Member>),
+    Error(Path, Box<Type>),
+    Map(FO),
+    Aliases(Path, Vec<Member>),
+}
+
+fn map_from_name_value<FO: FieldOp>(
+    name_value: &MetaNameValue,
+) -> syn::Result<FO> {
+    let ident = key_ident(&name_value.path)?;
+    let key = ident.to_string();
+    if !FO::NAME_VALUES.contains(&key.as_str()) {
+        return Err(unknown_option(&name_value.path, FO::NAME_VALUES));
+    }
+    let expr: Expr = match &name_value.lit {
+        // discriminants read better unquoted, like `value = 3`
+        Lit::Int(lit) if key == "value" => syn::parse_quote!(#lit),
+        // names are kept as string literals
+        Lit::Str(lit) if key == "name" => syn::parse_quote!(#lit),
+        lit => lit_parse(lit)?,
+    };
+    FO::from_key_expr(&key, expr)
+        .ok_or_else(|| unknown_option(&name_value.path, FO::NAME_VALUES))
+}
+
+fn map_from_path<FO: FieldOp>(path: &Path) -> syn::Result<FO> {
+    let ident = key_ident(path)?;
+    let key = ident.to_string();
+    FO::from_key(&key).ok_or_else(|| unknown_option(path, FO::PATHS))
+}
+
+fn kv_from_meta<FO: FieldOp>(meta: &Meta) -> syn::Result<KeyValue<FO>> {
+    Ok(KeyValue::Map(match meta {
+        Meta::NameValue(name_value) if path_eq(&name_value.path, "index") => {
+            return Ok(KeyValue::Rename(index_from_name_value(name_value)?));
+        }
+        Meta::NameValue(name_value) if path_eq(&name_value.path, "Error") => {
+            return Ok(KeyValue::Error(
+                name_value.path.clone(),
+                lit_parse(&name_value.lit)?,
+            ));
+        }
+        Meta::NameValue(name_value) if path_eq(&name_value.path, "path") => {
+            return Ok(KeyValue::Nested(
+                name_value.path.clone(),
+                members_from_name_value(name_value)?,
+            ));
+        }
+        Meta::NameValue(name_value) => map_from_name_value(name_value)?,
+        Meta::Path(path) => map_from_path(path)?,
+        Meta::List(list) => {
+            if path_eq(&list.path, "rename") {
+                let member = single_member_from_meta_list(list)?;
+                return Ok(KeyValue::Rename(member));
+            }
+            if path_eq(&list.path, "aliases") {
+                let members = members_from_meta_list(list)?;
+                return Ok(KeyValue::Aliases(list.path.clone(), members));
+            }
+            if path_eq(&list.path, "from_fields") {
+                let members = members_from_meta_list(list)?;
+                return Ok(KeyValue::Sources(list.path.clone(), members));
+            }
+            if path_eq(&list.path, "split") {
+                let members = members_from_meta_list(list)?;
+                return Ok(KeyValue::Split(list.path.clone(), members));
+            }
+            if path_eq(&list.path, "flatten") {
+                let members = members_from_meta_list(list)?;
+                return FO::from_key_members("flatten", members)
+                    .map(KeyValue::Map)
+                    .ok_or_else(|| unknown_option(&list.path, FO::PATHS));
+            }
+            return Err(unknown_option(
+                &list.path,
+                &["rename", "aliases", "from_fields", "split", "flatten"],
+            ));
+        }
+    }))
+}
+
+const DIRECTIONS: &[&str] = &["from", "from_self", "try_from", "try_from_self"];
+
+fn convert_items(attrs: &[Attribute]) -> syn::Result<Vec<NestedMeta>> {
+    let mut items = vec![];
+    for attr in attrs.iter().filter(|attr| path_eq(&attr.path, "convert")) {
+        items.extend(meta_list(attr)?.nested);
+    }
+    Ok(items)
+}
+
+fn is_direction(nested: &NestedMeta) -> bool {
+    matches!(
+        nested,
+        NestedMeta::Meta(Meta::List(list))
+            if DIRECTIONS.iter().any(|direction| path_eq(&list.path, direction))
+    )
+}
+
+fn attr_lists(
+    attrs: &[Attribute],
+    filter_path: &str,
+) -> syn::Result<Vec<MetaList>> {
+    let mut lists: Vec<_> = convert_items(attrs)?
+        .into_iter()
+        .filter_map(|nested| match nested {
+            NestedMeta::Meta(Meta::List(list))
+                if path_eq(&list.path, filter_path) =>
+            {
+                Some(list)
+            }
+            _ => None,
+        })
+        .collect();
+    if cfg!(feature = "legacy-attrs") {
+        for attr in attrs.iter().filter(|attr| path_eq(&attr.path, filter_path))
+        {
+            lists.push(meta_list(attr)?);
+        }
+    }
+    Ok(lists)
+}
+
+fn common_items(attrs: &[Attribute]) -> syn::Result<Vec<NestedMeta>> {
+    Ok(convert_items(attrs)?
+        .into_iter()
+        .filter(|nested| !is_direction(nested))
+        .collect())
+}
+
+fn check_common_keys(
+    input: &DeriveInput,
+    container_attrs: &ContainerAttrs,
+) -> syn::Result<()> {
+    let mut known: Vec<_> = container_attrs
+        .types()
+        .flat_map(|types| types.0.keys())
+        .map(Ident::to_string)
+        .collect();
+    known.sort();
+    known.dedup();
+    let attrs: Vec<&Attribute> = match &input.data {
+        Data::Struct(data) => {
+            data.fields.iter().flat_map(|field| &field.attrs).collect()
+        }
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .flat_map(|variant| {
+                let fields = variant.fields.iter();
+                variant
+                    .attrs
+                    .iter()
+                    .chain(fields.flat_map(|field| &field.attrs))
+            })
+            .collect(),
+        Data::Union(_) => vec![],
+    };
+    for attr in attrs {
+        for nested in common_items(std::slice::from_ref(attr))? {
+            match nested {
+                NestedMeta::Meta(Meta::List(list)) if is_key(&list) => {
+                    let key = key_ident(&list.path)?;
+                    if !known.contains(&key.to_string()) {
+                        return Err(syn::Error::new_spanned(
+                            &key,
+                            format!(
+                                "unknown key `{}`, expected {}",
+                                key,
+                                one_of(&known),
+                            ),
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+fn meta_list(attr: &Attribute) -> syn::Result<MetaList> {
+    let path = &attr.path;
+    let tokens = auto_as_lit(attr.tokens.clone());
+    match syn::parse2::<Meta>(quote!(#path #tokens))? {
+        Meta::List(list) => Ok(list),
+        meta => Err(syn::Error::new_spanned(
+            &meta,
+            format!(
+                "expected list, like `#[{}(...)]`",
+                attr.path.to_token_stream()
+            ),
+        )),
+    }
+}
+
+// Reads `Error = auto` as `Error = "auto"`, a `Meta` only takes literals.
+fn auto_as_lit(tokens: TokenStream2) -> TokenStream2 {
+    let mut tokens: Vec<_> = tokens.into_iter().collect();
+    for index in 0..tokens.len() {
+        let replaced = match &tokens[index] {
+            TokenTree::Group(group) => {
+                let mut replaced =
+                    Group::new(group.delimiter(), auto_as_lit(group.stream()));
+                replaced.set_span(group.span());
+                TokenTree::Group(replaced)
+            }
+            TokenTree::Ident(ident)
+                if ident == "auto"
+                    && matches!(
+                        &tokens[..index],
+                        [.., TokenTree::Ident(error), TokenTree::Punct(eq)]
+                            if error == "Error" && eq.as_char() == '='
+                    ) =>
+            {
+                let mut replaced = Literal::string("auto");
+                replaced.set_span(ident.span());
+                TokenTree::Literal(replaced)
+            }
+            _ => continue,
+        };
+        tokens[index] = replaced;
+    }
+    tokens.into_iter().collect()
+}
+
+fn lits_from_meta_list(meta_list: &MetaList) -> syn::Result<Vec<&Lit>> {
+    meta_list
+        .nested
+        .iter()
+        .map(|nested| match nested {
+            NestedMeta::Lit(lit) => Ok(lit),
+            NestedMeta::Meta(meta) => {
+                Err(syn::Error::new_spanned(meta, "expected string literal"))
+            }
+        })
+        .collect()
+}
+
+fn key_ident(path: &Path) -> syn::Result<Ident> {
+    path.get_ident().cloned().ok_or_else(|| {
+        syn::Error::new_spanned(path, "expected identifier, found path")
+    })
+}
+
+fn ident_from_meta(meta: &Meta) -> syn::Result<&Ident> {
+    match meta {
+        Meta::Path(path) => path.get_ident().ok_or_else(|| {
+            syn::Error::new_spanned(path, "expected identifier, found path")
+        }),
+        _ => Err(syn::Error::new_spanned(meta, "expected identifier")),
+    }
+}
+
+// `KEY(...)` lists, as opposed to list options like `rename(...)`
+fn is_key(list: &MetaList) -> bool {
+    !["rename", "aliases", "from_fields", "split", "flatten"]
+        .iter()
+        .any(|option| path_eq(&list.path, option))
+}
+
+fn path_eq(path: &Path, str: &str) -> bool {
+    path.get_ident()
+        .map(|ident| ident == str)
+        .unwrap_or_default()
+}
+
+fn single_member_from_meta_list(meta_list: &MetaList) -> syn::Result<Member> {
+    if meta_list.nested.len() != 1 {
+        return Err(syn::Error::new_spanned(
+            meta_list,
+            format!(
+                "expected exactly one name, like `{}(\"name\")`",
+                meta_list.path.to_token_stream()
+            ),
+        ));
+    }
+    let nested_meta = meta_list.nested.iter().next().unwrap();
+    Ok(match nested_meta {
+        NestedMeta::Meta(nested_meta) => {
+            Member::Named(ident_from_meta(nested_meta)?.clone())
+        }
+        NestedMeta::Lit(lit) => lit_parse(lit)?,
+    })
+}
+
+fn members_from_meta_list(meta_list: &MetaList) -> syn::Result<Vec<Member>> {
+    if meta_list.nested.is_empty() {
+        return Err(syn::Error::new_spanned(
+            meta_list,
+            format!(
+                "expected at least one name, like `{}(\"name\")`",
+                meta_list.path.to_token_stream()
+            ),
+        ));
+    }
+    meta_list
+        .nested
+        .iter()
+        .map(|nested_meta| match nested_meta {
+            NestedMeta::Meta(nested_meta) => {
+                Ok(Member::Named(ident_from_meta(nested_meta)?.clone()))
+            }
+            NestedMeta::Lit(lit) => lit_parse(lit),
+        })
+        .collect()
+}
+
+fn members_from_name_value(
+    name_value: &MetaNameValue,
+) -> syn::Result<Vec<Member>> {
+    let members = lit_str(&name_value.lit)?
+        .parse_with(Punctuated::<Member, Token![.]>::parse_separated_nonempty)
+        .map_err(|err| {
+            syn::Error::new(
+                err.span(),
+                "expected field path, like `path = \"meta.created_at\"`",
+            )
+        })?;
+    Ok(members.into_iter().collect())
+}
+
+fn index_from_name_value(name_value: &MetaNameValue) -> syn::Result<Member> {
+    if let Lit::Int(lit_int) = &name_value.lit {
+        Ok(Member::Unnamed(Index {
+            index: lit_int.base10_parse()?,
+            span: lit_int.span(),
+        }))
+    } else {
+        Err(syn::Error::new_spanned(
+            &name_value.lit,
+            "expected integer literal, like `index = 0`",
+        ))
+    }
+}
+
+fn lit_parse<T: Parse>(lit: &Lit) -> syn::Result<T> {
+    if let Lit::Str(lit_str) = lit {
+        lit_str.parse()
+    } else {
+        Err(syn::Error::new_spanned(lit, "expected string literal"))
+    }
+}
+
+fn lit_str(lit: &Lit) -> syn::Result<&LitStr> {
+    if let Lit::Str(lit_str) = lit {
+        Ok(lit_str)
+    } else {
+        Err(syn::Error::new_spanned(lit, "expected string literal"))
+    }
+}
+
+fn lit_parse_bounds(lit: &Lit) -> syn::Result<Vec<WherePredicate>> {
+    if let Lit::Str(lit_str) = lit {
+        let parser = Punctuated::<WherePredicate, Token![,]>::parse_terminated;
+        lit_str
+            .parse_with(parser)
+            .map(|bounds| bounds.into_iter().collect())
+    } else {
+        Err(syn::Error::new_spanned(lit, "expected string literal"))
+    }
+}
+
+fn duplicate<T: ToTokens>(tokens: T) -> syn::Error {
+    syn::Error::new_spanned(tokens, "duplicate attribute")
+}
+
+fn unknown_option(path: &Path, expected: &[&str]) -> syn::Error {
+    let message = if expected.is_empty() {
+        format!("option `{}` isn't supported here", path.to_token_stream())
+    } else {
+        format!(
+            "unknown option `{}`, expected {}",
+            path.to_token_stream(),
+            one_of(expected)
+        )
+    };
+    syn::Error::new_spanned(path, message)
+}
+
+fn cant_rename(rename_to: Option<Member>) -> syn::Error {
+    syn::Error::new_spanned(
+        rename_to,
+        "`rename` and `index` can't be combined with \
+        `default`, `skip`, `new`, `compute` or `flatten`",
+    )
+}
+
+fn cant_nest(path: Path) -> syn::Error {
+    syn::Error::new_spanned(
+        path,
+        "`path` can't be combined with \
+        `default`, `skip`, `new`, `compute` or `flatten`",
+    )
+}
+
+fn needs_map(path: Path) -> syn::Error {
+    syn::Error::new_spanned(
+        &path,
+        format!("`{}` needs `map` or `try_map`", path.to_token_stream()),
+    )
+}
+
+fn cant_alias(path: Path) -> syn::Error {
+    syn::Error::new_spanned(
+        path,
+        "`aliases` can't be combined with `default`, `skip` or `error`",
+    )
+}
+
+fn one_of<S: AsRef<str>>(expected: &[S]) -> String {
+    let expected: Vec<_> = expected
+        .iter()
+        .map(|expected| format!("`{}`", expected.as_ref()))
+        .collect();
+    match expected.as_slice() {
+        [] => String::new(),
+        [single] => single.clone(),
+        [init @ .., last] => format!("one of {} or {}", init.join(", "), last),
     }
 }
@@ -0,0 +1,140 @@
+use std::{convert::Infallible, num::TryFromIntError};
+
+use derive_convert::Convert;
+
+#[derive(PartialEq, Debug)]
+enum FieldError {
+    Range,
+    Empty,
+}
+
+impl From<TryFromIntError> for FieldError {
+    fn from(_: TryFromIntError) -> Self {
+        FieldError::Range
+    }
+}
+
+impl From<Infallible> for FieldError {
+    fn from(error: Infallible) -> Self {
+        match error {}
+    }
+}
+
+type Errors = Vec<(&'static str, FieldError)>;
+
+fn non_empty(name: String) -> Result<String, FieldError> {
+    if name.is_empty() {
+        Err(FieldError::Empty)
+    } else {
+        Ok(name)
+    }
+}
+
+fn by_field<E: Into<FieldError>>(
+    error: E,
+    field: &'static str,
+    _foreign: &'static str,
+) -> (&'static str, FieldError) {
+    (field, error.into())
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(try_from(
+    Form = "form::User",
+    Tagged("form::TaggedUser", context = "by_field"),
+    Error = "Errors",
+    accumulate_errors
+))]
+struct User {
+    #[convert(
+        try_map = "non_empty",
+        Tagged(rename(login), try_map = "non_empty")
+    )]
+    name: String,
+    #[convert(Form(rename(years)))]
+    age: u8,
+    #[convert(Form(flatten), Tagged(default))]
+    address: Address,
+}
+
+#[derive(Convert, Default, PartialEq, Debug)]
+#[convert(try_from(
+    Form("form::User", ignore_rest),
+    Error = "Errors",
+    accumulate_errors
+))]
+struct Address {
+    #[convert(try_map = "non_empty")]
+    city: String,
+    zip: u16,
+}
+
+mod form {
+    #[derive(Clone)]
+    pub struct User {
+        pub name: String,
+        pub years: i32,
+        pub city: String,
+        pub zip: i32,
+    }
+
+    pub struct TaggedUser {
+        pub login: String,
+        pub age: i32,
+    }
+}
+
+#[test]
+fn accumulate_ok() {
+    let user = User::try_from(form::User {
+        name: "ada".into(),
+        years: 36,
+        city: "london".into(),
+        zip: 1,
+    });
+    assert_eq!(
+        user,
+        Ok(User {
+            name: "ada".into(),
+            age: 36,
+            address: Address {
+                city: "london".into(),
+                zip: 1,
+            },
+        })
+    );
+}
+
+#[test]
+fn accumulate_all_errors() {
+    let user = User::try_from(form::User {
+        name: String::new(),
+        years: -1,
+        city: String::new(),
+        zip: -1,
+    });
+    assert_eq!(
+        user,
+        Err(vec![
+            ("city", FieldError::Empty),
+            ("zip", FieldError::Range),
+            ("name", FieldError::Empty),
+            ("age", FieldError::Range),
+        ])
+    );
+}
+
+#[test]
+fn accumulate_with_context() {
+    let user = User::try_from(form::TaggedUser {
+        login: String::new(),
+        age: 300,
+    });
+    assert_eq!(
+        user,
+        Err(vec![
+            ("name", FieldError::Empty),
+            ("age", FieldError::Range)
+        ])
+    );
+}
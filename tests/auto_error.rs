@@ -0,0 +1,150 @@
+use std::{error::Error, fmt, num::TryFromIntError};
+
+use derive_convert::Convert;
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(
+    try_from(V1 = "v1::Rect1", Error = auto),
+    try_from_self(V2 = "v2::Rect2", Error = "auto")
+)]
+struct Rect {
+    #[convert(Error = "TryFromIntError")]
+    x: i32,
+    #[convert(
+        V1(try_map = "positive", Error = "Negative"),
+        V2(Error = "TryFromIntError")
+    )]
+    width: u32,
+    // converts infallibly, without a variant
+    height: u32,
+}
+
+#[derive(Debug)]
+struct Negative(i64);
+
+impl fmt::Display for Negative {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} isn't positive", self.0)
+    }
+}
+
+impl Error for Negative {}
+
+fn positive(width: i64) -> Result<u32, Negative> {
+    u32::try_from(width).map_err(|_| Negative(width))
+}
+
+mod v1 {
+    pub struct Rect1 {
+        pub x: i64,
+        pub width: i64,
+        pub height: u32,
+    }
+
+    pub struct Size {
+        pub len: i64,
+    }
+}
+
+mod v2 {
+    #[derive(PartialEq, Debug)]
+    pub struct Rect2 {
+        pub x: i8,
+        pub width: u8,
+        pub height: u32,
+    }
+
+    pub struct Size {
+        pub len: i64,
+    }
+}
+
+#[test]
+fn auto_error_try_from() {
+    let rect = Rect::try_from(v1::Rect1 {
+        x: 1,
+        width: 2,
+        height: 3,
+    });
+    assert_eq!(
+        rect.ok(),
+        Some(Rect {
+            x: 1,
+            width: 2,
+            height: 3
+        })
+    );
+
+    let error = Rect::try_from(v1::Rect1 {
+        x: 1,
+        width: -2,
+        height: 3,
+    })
+    .unwrap_err();
+    assert!(matches!(error, RectFromRect1Error::Width(Negative(-2))));
+    assert_eq!(
+        error.to_string(),
+        "failed to convert field `width`: -2 isn't positive"
+    );
+
+    let error = Rect::try_from(v1::Rect1 {
+        x: 1 << 40,
+        width: 2,
+        height: 3,
+    })
+    .unwrap_err();
+    assert!(matches!(error, RectFromRect1Error::X(_)));
+    assert!(error.source().unwrap().is::<TryFromIntError>());
+}
+
+#[test]
+fn auto_error_try_from_self() {
+    let rect = v2::Rect2::try_from(Rect {
+        x: 1,
+        width: 2,
+        height: 3,
+    });
+    assert_eq!(
+        rect.ok(),
+        Some(v2::Rect2 {
+            x: 1,
+            width: 2,
+            height: 3
+        })
+    );
+
+    let error = v2::Rect2::try_from(Rect {
+        x: 1,
+        width: 256,
+        height: 3,
+    })
+    .unwrap_err();
+    assert!(matches!(error, Rect2FromRectError::Width(_)));
+}
+
+// the foreign types share a name, so the enums are named after the keys
+#[derive(Convert, PartialEq, Debug)]
+#[convert(
+    try_from(V1 = "v1::Size", V2 = "v2::Size", Error = auto),
+    try_from_self(V1 = "v1::Size", Error = auto)
+)]
+struct Size {
+    #[convert(try_from(
+        try_map = "|len: i64| -> Result<u32, TryFromIntError> { len.try_into() }"
+    ))]
+    len: u32,
+}
+
+#[test]
+fn auto_error_shared_name() {
+    let error = Size::try_from(v1::Size { len: -1 }).unwrap_err();
+    assert!(matches!(error, SizeFromV1Error::Len(_)));
+    let error = Size::try_from(v2::Size { len: -1 }).unwrap_err();
+    assert!(matches!(error, SizeFromV2Error::Len(_)));
+
+    let size = v1::Size::try_from(Size { len: 1 });
+    assert!(matches!(size, Ok(v1::Size { len: 1 })));
+    // the field converts infallibly, so the enum has no variants
+    let _: fn(V1FromSizeError) -> std::convert::Infallible =
+        |error| match error {};
+}
@@ -0,0 +1,85 @@
+use std::num::TryFromIntError;
+
+use derive_convert::Convert;
+
+#[derive(Convert, PartialEq, Debug, Clone)]
+#[convert(from(V1("User1", both)), from_self(V2 = "User2"))]
+struct User {
+    #[convert(from(V1(rename("login"))))]
+    name: String,
+    #[convert(from(V1(map = "Age", inverse = "|age: Age| age.0")))]
+    age: Age,
+    #[convert(from(V1(skip, default)))]
+    cache: Vec<u8>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+struct Age(u32);
+
+#[derive(PartialEq, Debug, Clone)]
+struct User1 {
+    login: String,
+    age: u32,
+}
+
+#[derive(PartialEq, Debug)]
+struct User2 {
+    name: String,
+    age: Age,
+    cache: Vec<u8>,
+}
+
+#[derive(Convert, PartialEq, Debug, Clone, Copy)]
+#[convert(try_from_self(
+    V1("Level1", bidirectional),
+    Error = "TryFromIntError"
+))]
+enum Level {
+    Low(
+        #[convert(try_from_self(V1(
+            try_map = "u8::try_from",
+            try_inverse = "u16::try_from"
+        )))]
+        u16,
+    ),
+    #[convert(try_from_self(V1(rename("Top"))))]
+    High,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Level1 {
+    Low(u8),
+    Top,
+}
+
+#[test]
+fn both_from() {
+    let user1 = User1 {
+        login: "login".into(),
+        age: 30,
+    };
+    let user = User {
+        name: "login".into(),
+        age: Age(30),
+        cache: vec![],
+    };
+    assert_eq!(User::from(user1.clone()), user);
+    assert_eq!(User1::from(user.clone()), user1);
+    assert_eq!(
+        User2::from(user),
+        User2 {
+            name: "login".into(),
+            age: Age(30),
+            cache: vec![],
+        }
+    );
+}
+
+#[test]
+fn both_try_from() {
+    assert_eq!(Level1::try_from(Level::Low(3)), Ok(Level1::Low(3)));
+    assert!(Level1::try_from(Level::Low(300)).is_err());
+    assert_eq!(Level1::try_from(Level::High), Ok(Level1::Top));
+    assert_eq!(Level::try_from(Level1::Low(3)), Ok(Level::Low(3)));
+    assert_eq!(Level::try_from(Level1::Top), Ok(Level::High));
+}
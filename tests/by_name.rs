@@ -0,0 +1,69 @@
+use std::str::FromStr;
+
+use derive_convert::Convert;
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(try_from(
+    STR("&str", by_name, rename_all = "snake_case"),
+    Error = "UnknownColor",
+    error = "UnknownColor"
+))]
+enum Color {
+    Red,
+    DarkBlue,
+    #[convert(try_from(name = "light-green"))]
+    LightGreen,
+    #[convert(try_from(skip))]
+    Other,
+}
+
+#[derive(PartialEq, Debug)]
+struct UnknownColor;
+
+#[test]
+fn try_from_name() {
+    assert_eq!(Color::try_from("red"), Ok(Color::Red));
+    assert_eq!(Color::try_from("dark_blue"), Ok(Color::DarkBlue));
+    assert_eq!(Color::try_from("light-green"), Ok(Color::LightGreen));
+    assert_eq!(Color::try_from("Red"), Err(UnknownColor));
+    assert_eq!(Color::try_from("other"), Err(UnknownColor));
+    assert_eq!(Color::from_str("dark_blue"), Ok(Color::DarkBlue));
+    assert_eq!("red".parse::<Color>(), Ok(Color::Red));
+    assert_eq!(<&str>::from(Color::DarkBlue), "dark_blue");
+    assert_eq!(<&str>::from(Color::LightGreen), "light-green");
+    assert_eq!(<&str>::from(Color::Other), "other");
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(
+    try_from(
+        STRING(
+            "String",
+            rename_all = "SCREAMING_SNAKE_CASE",
+            case_insensitive,
+            fallback = "Unknown"
+        ),
+        Error = "UnknownColor",
+        by_name
+    ),
+    from_self(LABEL("&'static str", by_name, rename_all = "kebab-case"))
+)]
+enum Status {
+    InProgress,
+    #[convert(STRING(rename(DONE), aliases(FINISHED)))]
+    Done,
+    Unknown,
+}
+
+#[test]
+fn try_from_name_case_insensitive() {
+    assert_eq!(
+        Status::try_from("in_progress".to_owned()),
+        Ok(Status::InProgress)
+    );
+    assert_eq!(Status::try_from("Done".to_owned()), Ok(Status::Done));
+    assert_eq!(Status::try_from("finished".to_owned()), Ok(Status::Done));
+    assert_eq!(Status::try_from("nope".to_owned()), Ok(Status::Unknown));
+    assert_eq!(String::from(Status::InProgress), "IN_PROGRESS");
+    assert_eq!(<&str>::from(Status::InProgress), "in-progress");
+}
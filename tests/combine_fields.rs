@@ -0,0 +1,169 @@
+use derive_convert::Convert;
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(
+    from(V1 = "v1::Person"),
+    try_from(V2 = "v2::Person", Error = "String")
+)]
+struct Person {
+    #[convert(
+        from(V1(
+            from_fields(first, last),
+            map_ref = "|first: &String, last: &String| format!(\"{} {}\", first, last)"
+        )),
+        try_from(V2(
+            rename(name),
+            try_map = "split_name",
+            split(first, last)
+        ))
+    )]
+    first: String,
+    last: String,
+    #[convert(from(V1(from_fields("year", "month"), map = "to_months")))]
+    #[convert(try_from(V2(rename(months), try_map = "positive")))]
+    months: u32,
+}
+
+fn split_name(name: String) -> Result<(String, String), String> {
+    match name.split_once(' ') {
+        Some((first, last)) => Ok((first.into(), last.into())),
+        None => Err(name),
+    }
+}
+
+fn to_months(year: u16, month: u8) -> u32 {
+    u32::from(year) * 12 + u32::from(month)
+}
+
+fn positive(months: i64) -> Result<u32, String> {
+    months.try_into().map_err(|_| months.to_string())
+}
+
+mod v1 {
+    pub struct Person {
+        pub first: String,
+        pub last: String,
+        pub year: u16,
+        pub month: u8,
+    }
+
+    pub struct Date {
+        pub year: u16,
+        pub month: u8,
+    }
+}
+
+mod v2 {
+    pub struct Person {
+        pub name: String,
+        pub months: i64,
+    }
+}
+
+#[test]
+fn from_fields() {
+    let person = Person::from(v1::Person {
+        first: "Ada".into(),
+        last: "Lovelace".into(),
+        year: 2,
+        month: 3,
+    });
+    assert_eq!(
+        person,
+        Person {
+            first: "Ada Lovelace".into(),
+            last: "Lovelace".into(),
+            months: 27,
+        }
+    );
+}
+
+#[test]
+fn split() {
+    let person = Person::try_from(v2::Person {
+        name: "Ada Lovelace".into(),
+        months: 27,
+    });
+    assert_eq!(
+        person,
+        Ok(Person {
+            first: "Ada".into(),
+            last: "Lovelace".into(),
+            months: 27,
+        })
+    );
+    let person = Person::try_from(v2::Person {
+        name: "Ada".into(),
+        months: 27,
+    });
+    assert_eq!(person, Err("Ada".into()));
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(
+    from(V1("v1::Person", ignore("year", "month"))),
+    try_from(V2("v2::Person", ignore("months")), Error = "String")
+)]
+struct Name {
+    #[convert(from(from_fields(first, last), map_ref = "join"))]
+    #[convert(try_from(
+        rename(name),
+        try_map = "split_name",
+        split(first, last)
+    ))]
+    first: String,
+    #[convert(from(from_fields(first, last), map_ref = "last"))]
+    last: String,
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(
+    from(V1("v1::Person", ignore("first", "last"))),
+    from(Date = "v1::Date")
+)]
+struct Age {
+    #[convert(from_fields(year, month), map = "to_months")]
+    months: u32,
+}
+
+fn join(first: &str, last: &str) -> String {
+    format!("{} {}", first, last)
+}
+
+fn last(_first: &str, last: &str) -> String {
+    last.into()
+}
+
+#[test]
+fn keyless_from_fields() {
+    let name = Name::from(v1::Person {
+        first: "Ada".into(),
+        last: "Lovelace".into(),
+        year: 2,
+        month: 3,
+    });
+    assert_eq!(
+        name,
+        Name {
+            first: "Ada Lovelace".into(),
+            last: "Lovelace".into(),
+        }
+    );
+    let age = Age::from(v1::Date { year: 2, month: 3 });
+    assert_eq!(age, Age { months: 27 });
+}
+
+#[test]
+fn keyless_split() {
+    let name = Name::try_from(v2::Person {
+        name: "Ada Lovelace".into(),
+        months: 27,
+    });
+    assert_eq!(
+        name,
+        Ok(Name {
+            first: "Ada".into(),
+            last: "Lovelace".into(),
+        })
+    );
+}
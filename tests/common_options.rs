@@ -0,0 +1,113 @@
+use std::num::TryFromIntError;
+
+use derive_convert::Convert;
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(
+    from(V1("Sample1", ignore("retries"))),
+    from_self(V1 = "Sample1", V2 = "Sample2"),
+    try_from(V2("Sample2", ignore("retries")), Error = "TryFromIntError")
+)]
+struct Sample {
+    #[convert(V2(rename("Height")))]
+    height: u32,
+    #[convert(map = "Celsius", inverse = "|c: Celsius| c.0")]
+    temperature: Celsius,
+    #[convert(V1(skip, default))]
+    note: String,
+    #[convert(V2(try_map = "u8::try_from", inverse = "u32::from"))]
+    level: u8,
+    #[convert(default, try_from(V2(new = "|| 7")))]
+    retries: u32,
+}
+
+#[derive(PartialEq, Debug)]
+struct Celsius(i32);
+
+#[derive(PartialEq, Debug)]
+struct Sample1 {
+    height: u32,
+    temperature: i32,
+    level: u8,
+    retries: u32,
+}
+
+#[derive(PartialEq, Debug)]
+#[allow(non_snake_case)]
+struct Sample2 {
+    Height: u32,
+    temperature: i32,
+    note: String,
+    level: u32,
+    retries: u32,
+}
+
+#[test]
+fn common_options_from() {
+    let sample1 = Sample1 {
+        height: 1,
+        temperature: 20,
+        level: 3,
+        retries: 4,
+    };
+    assert_eq!(
+        Sample::from(sample1),
+        Sample {
+            height: 1,
+            temperature: Celsius(20),
+            note: String::new(),
+            level: 3,
+            retries: 0,
+        }
+    );
+}
+
+#[test]
+fn common_options_try_from() {
+    let sample2 = Sample2 {
+        Height: 1,
+        temperature: -5,
+        note: "note".into(),
+        level: 3,
+        retries: 4,
+    };
+    assert_eq!(
+        Sample::try_from(sample2),
+        Ok(Sample {
+            height: 1,
+            temperature: Celsius(-5),
+            note: "note".into(),
+            level: 3,
+            retries: 7,
+        })
+    );
+    let sample2 = Sample2 {
+        Height: 1,
+        temperature: -5,
+        note: "note".into(),
+        level: 300,
+        retries: 4,
+    };
+    assert!(Sample::try_from(sample2).is_err());
+}
+
+#[test]
+fn common_options_from_self() {
+    let sample = Sample {
+        height: 1,
+        temperature: Celsius(20),
+        note: "note".into(),
+        level: 3,
+        retries: 4,
+    };
+    assert_eq!(
+        Sample2::from(sample),
+        Sample2 {
+            Height: 1,
+            temperature: 20,
+            note: "note".into(),
+            level: 3,
+            retries: 4,
+        }
+    );
+}
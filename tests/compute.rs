@@ -0,0 +1,127 @@
+use derive_convert::Convert;
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(
+    from(Dto("RectDto", ignore("area"))),
+    from_self(Dto = "RectDto"),
+    try_from(Wide = "WideRectDto", Error = "std::num::TryFromIntError")
+)]
+struct Rect {
+    width: u32,
+    height: u32,
+    #[convert(
+        compute = "width * height",
+        Wide(compute = "u32::try_from(u64::from(width) * u64::from(height))?")
+    )]
+    area: u32,
+    #[convert(
+        Dto(rename(name)),
+        Wide(compute = "format!(\"{}x{}\", width, height)")
+    )]
+    label: String,
+    #[convert(
+        compute = "label.len()",
+        from_self(Dto(skip)),
+        Wide(compute = "label.chars().count()")
+    )]
+    label_len: usize,
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(from_self(Tagged = "TaggedPair"))]
+struct Pair(
+    u32,
+    String,
+    #[convert(from_self(Tagged(compute = "format!(\"{}:{}\", _0, _1)")))] (),
+);
+
+#[derive(PartialEq, Debug)]
+struct RectDto {
+    width: u32,
+    height: u32,
+    area: u32,
+    name: String,
+}
+
+#[derive(Clone)]
+struct WideRectDto {
+    width: u64,
+    height: u64,
+}
+
+#[derive(PartialEq, Debug)]
+struct TaggedPair(u32, String, String);
+
+#[test]
+fn compute_from() {
+    let rect = Rect::from(RectDto {
+        width: 2,
+        height: 3,
+        area: 0,
+        name: "a".into(),
+    });
+    assert_eq!(
+        rect,
+        Rect {
+            width: 2,
+            height: 3,
+            area: 6,
+            label: "a".into(),
+            label_len: 1,
+        }
+    );
+}
+
+#[test]
+fn compute_from_self() {
+    let rect = Rect {
+        width: 2,
+        height: 3,
+        area: 6,
+        label: "a".into(),
+        label_len: 1,
+    };
+    assert_eq!(
+        RectDto::from(rect),
+        RectDto {
+            width: 2,
+            height: 3,
+            area: 6,
+            name: "a".into(),
+        }
+    );
+    let pair = TaggedPair::from(Pair(1, "a".into(), ()));
+    assert_eq!(pair, TaggedPair(1, "a".into(), "1:a".into()));
+}
+
+#[test]
+fn compute_try_from() {
+    let dto = WideRectDto {
+        width: 2,
+        height: 3,
+    };
+    assert_eq!(
+        Rect::try_from(dto.clone()),
+        Ok(Rect {
+            width: 2,
+            height: 3,
+            area: 6,
+            label: "2x3".into(),
+            label_len: 3,
+        })
+    );
+    assert!(
+        Rect::try_from(WideRectDto {
+            width: 1 << 20,
+            height: 1 << 20,
+        })
+        .is_err()
+    );
+    assert!(
+        Rect::try_from(WideRectDto {
+            width: 1 << 40,
+            ..dto
+        })
+        .is_err()
+    );
+}
@@ -0,0 +1,171 @@
+use std::{convert::Infallible, num::TryFromIntError};
+
+use derive_convert::Convert;
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(from(V1 = "Event1"))]
+#[convert(try_from_self(V1 = "Event1", Error = "Error"))]
+enum Event {
+    Moved {
+        x: i32,
+        #[convert(from(map = "i32::from"))]
+        #[convert(try_from_self(try_map = "i16::try_from"))]
+        y: i32,
+    },
+    Clicked(
+        #[convert(from(V1(rename("1"))))]
+        #[convert(try_from_self(V1(index = 1)))]
+        u8,
+        #[convert(from(V1(index = 0)))]
+        #[convert(try_from_self(V1(rename("0"))))]
+        bool,
+    ),
+    Resized {
+        #[convert(from(V1(rename("w"))))]
+        #[convert(try_from_self(V1(rename("w"))))]
+        width: u32,
+        #[convert(from(V1(default)))]
+        #[convert(try_from_self(V1(skip)))]
+        height: u32,
+        #[convert(from(V1(new = "|| 7")))]
+        #[convert(try_from_self(V1(skip)))]
+        depth: u32,
+    },
+    Closed,
+}
+
+#[derive(PartialEq, Debug)]
+enum Event1 {
+    Moved { x: i32, y: i16 },
+    Clicked(bool, u8),
+    Resized { w: u32 },
+    Closed,
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(from(V1 = "Shape1<T>", bound = "T: Clone"))]
+enum Shape<T> {
+    Circle(T),
+    #[convert(from(V1(rename("Square"))))]
+    Rect {
+        #[convert(from(map_ref = "Clone::clone", rename("side")))]
+        width: T,
+        #[convert(from(rename("side")))]
+        height: T,
+    },
+    #[convert(from(V1(default)))]
+    Unknown {
+        tag: String,
+    },
+}
+
+impl<T> Default for Shape<T> {
+    fn default() -> Self {
+        Shape::Unknown {
+            tag: "default".into(),
+        }
+    }
+}
+
+enum Shape1<T> {
+    Circle(T),
+    Square { side: T },
+    Unknown { _tag: &'static str },
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(from(V1("Move1", ignore_rest)))]
+enum Move {
+    Moved { x: i32, y: i32 },
+}
+
+#[allow(dead_code)]
+enum Move1 {
+    Moved { x: i32, y: i32, z: i32 },
+}
+
+#[derive(Debug)]
+enum Error {
+    TryFromInt,
+}
+
+impl From<Infallible> for Error {
+    fn from(value: Infallible) -> Self {
+        match value {}
+    }
+}
+
+impl From<TryFromIntError> for Error {
+    fn from(_: TryFromIntError) -> Self {
+        Self::TryFromInt
+    }
+}
+
+#[test]
+fn from_data_enum() {
+    assert_eq!(
+        Event::from(Event1::Moved { x: 1, y: 2 }),
+        Event::Moved { x: 1, y: 2 }
+    );
+    assert_eq!(
+        Event::from(Event1::Clicked(true, 3)),
+        Event::Clicked(3, true)
+    );
+    assert_eq!(
+        Event::from(Event1::Resized { w: 4 }),
+        Event::Resized {
+            width: 4,
+            height: 0,
+            depth: 7,
+        }
+    );
+    assert_eq!(Event::from(Event1::Closed), Event::Closed);
+}
+
+#[test]
+fn try_from_self_data_enum() {
+    assert_eq!(
+        Event1::try_from(Event::Moved { x: 1, y: 2 }).unwrap(),
+        Event1::Moved { x: 1, y: 2 }
+    );
+    assert!(Event1::try_from(Event::Moved { x: 1, y: 1 << 20 }).is_err());
+    assert_eq!(
+        Event1::try_from(Event::Clicked(3, true)).unwrap(),
+        Event1::Clicked(true, 3)
+    );
+    assert_eq!(
+        Event1::try_from(Event::Resized {
+            width: 4,
+            height: 5,
+            depth: 6,
+        })
+        .unwrap(),
+        Event1::Resized { w: 4 }
+    );
+}
+
+#[test]
+fn generic_data_enum() {
+    assert_eq!(Shape::from(Shape1::Circle(1.0)), Shape::Circle(1.0));
+    assert_eq!(
+        Shape::from(Shape1::Square { side: 2 }),
+        Shape::Rect {
+            width: 2,
+            height: 2,
+        }
+    );
+    assert_eq!(
+        Shape::<u8>::from(Shape1::Unknown { _tag: "foo" }),
+        Shape::Unknown {
+            tag: "default".into(),
+        }
+    );
+}
+
+#[test]
+fn ignore_rest_data_enum() {
+    assert_eq!(
+        Move::from(Move1::Moved { x: 1, y: 2, z: 3 }),
+        Move::Moved { x: 1, y: 2 }
+    );
+}
@@ -0,0 +1,107 @@
+use derive_convert::Convert;
+
+#[derive(Convert, PartialEq, Debug, Clone, Copy)]
+#[convert(try_from(
+    RAW = "u8",
+    Error = "InvalidColor",
+    error = "InvalidColor",
+    by_discriminant
+))]
+enum Color {
+    Red = 1,
+    Green,
+    #[convert(try_from(value = 7))]
+    Blue,
+    #[convert(try_from(skip))]
+    Reserved,
+}
+
+#[derive(PartialEq, Debug)]
+struct InvalidColor;
+
+#[test]
+fn try_from_discriminant() {
+    assert_eq!(Color::try_from(1u8), Ok(Color::Red));
+    assert_eq!(Color::try_from(2u8), Ok(Color::Green));
+    assert_eq!(Color::try_from(7u8), Ok(Color::Blue));
+    assert_eq!(Color::try_from(3u8), Err(InvalidColor));
+    assert_eq!(u8::from(Color::Green), 2);
+    assert_eq!(u8::from(Color::Blue), 7);
+    assert_eq!(u8::from(Color::Reserved), 4);
+    assert_eq!(Color::try_from(4u8), Err(InvalidColor));
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(
+    try_from(
+        WIRE("i32", by_discriminant, fallback = "Unknown"),
+        Error = "InvalidColor"
+    ),
+    from_self(LEGACY("u16", by_discriminant))
+)]
+enum Level {
+    Low,
+    High = 10,
+    #[convert(WIRE(value = "-1"))]
+    Unknown,
+}
+
+#[test]
+fn discriminant_fallback() {
+    assert_eq!(Level::try_from(10i32), Ok(Level::High));
+    assert_eq!(Level::try_from(-1i32), Ok(Level::Unknown));
+    assert_eq!(Level::try_from(5i32), Ok(Level::Unknown));
+    assert_eq!(i32::from(Level::Unknown), -1);
+    assert_eq!(u16::from(Level::Low), 0);
+    assert_eq!(u16::from(Level::Unknown), 11);
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(from_self(RAW("i8", by_discriminant)))]
+#[repr(i16)]
+enum Edge {
+    Min = -128,
+    Max = 127,
+}
+
+#[test]
+fn discriminant_bounds() {
+    assert_eq!(i8::from(Edge::Min), -128);
+    assert_eq!(i8::from(Edge::Max), 127);
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(from_self(
+    WIDE("u128", by_discriminant),
+    NARROW("u64", by_discriminant)
+))]
+#[repr(u128)]
+enum Huge {
+    #[convert(NARROW(value = 0))]
+    Top = u128::MAX,
+    Small = 1,
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(try_from(
+    RAW = "u128",
+    Error = "InvalidColor",
+    error = "InvalidColor",
+    by_discriminant
+))]
+#[repr(i128)]
+enum Positive {
+    Zero,
+    Top = i128::MAX,
+}
+
+#[test]
+fn discriminant_u128() {
+    assert_eq!(u128::from(Huge::Top), u128::MAX);
+    assert_eq!(u128::from(Huge::Small), 1);
+    assert_eq!(u64::from(Huge::Top), 0);
+    assert_eq!(u64::from(Huge::Small), 1);
+    assert_eq!(Positive::try_from(i128::MAX as u128), Ok(Positive::Top));
+    assert_eq!(Positive::try_from(0u128), Ok(Positive::Zero));
+    assert_eq!(Positive::try_from(u128::MAX), Err(InvalidColor));
+}
@@ -0,0 +1,67 @@
+use derive_convert::Convert;
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(
+    from_self(V2("v2::Rect2", extra(_other2 = "-1", label(default)))),
+    try_from_self(
+        V3("v2::Rect3", extra(_other3(default), depth = "16")),
+        Error = "std::num::TryFromIntError"
+    )
+)]
+struct Rect {
+    x: i32,
+    width: i32,
+}
+
+mod v2 {
+    #[derive(PartialEq, Debug)]
+    pub struct Rect2 {
+        pub x: i32,
+        pub width: i32,
+        pub _other2: i32,
+        pub label: String,
+    }
+
+    #[derive(PartialEq, Debug)]
+    pub struct Rect3 {
+        pub x: i16,
+        pub width: i16,
+        pub _other3: bool,
+        pub depth: u8,
+    }
+}
+
+#[test]
+fn extra_from_self() {
+    let rect2 = v2::Rect2::from(Rect { x: 1, width: 2 });
+    assert_eq!(
+        rect2,
+        v2::Rect2 {
+            x: 1,
+            width: 2,
+            _other2: -1,
+            label: String::new(),
+        }
+    );
+}
+
+#[test]
+fn extra_try_from_self() {
+    let rect3 = v2::Rect3::try_from(Rect { x: 1, width: 2 });
+    assert_eq!(
+        rect3,
+        Ok(v2::Rect3 {
+            x: 1,
+            width: 2,
+            _other3: false,
+            depth: 16,
+        })
+    );
+    assert!(
+        v2::Rect3::try_from(Rect {
+            x: 1 << 20,
+            width: 2
+        })
+        .is_err()
+    );
+}
@@ -0,0 +1,199 @@
+use std::{convert::Infallible, num::TryFromIntError};
+
+use derive_convert::Convert;
+
+#[derive(PartialEq, Debug)]
+struct ConvertError {
+    path: String,
+    message: String,
+}
+
+impl ConvertError {
+    fn at(mut self, segment: &str) -> Self {
+        if segment.is_empty() {
+            return self;
+        }
+        if !self.path.is_empty() && !self.path.starts_with('[') {
+            self.path.insert(0, '.');
+        }
+        self.path.insert_str(0, segment);
+        self
+    }
+}
+
+impl From<TryFromIntError> for ConvertError {
+    fn from(error: TryFromIntError) -> Self {
+        ConvertError {
+            path: String::new(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<Infallible> for ConvertError {
+    fn from(error: Infallible) -> Self {
+        match error {}
+    }
+}
+
+fn by_foreign<E: Into<ConvertError>>(
+    error: E,
+    _field: &str,
+    foreign: &str,
+) -> ConvertError {
+    error.into().at(foreign)
+}
+
+fn by_field<E: Into<ConvertError>>(
+    error: E,
+    field: &str,
+    _foreign: &str,
+) -> ConvertError {
+    error.into().at(field)
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(try_from(
+    Wire = "wire::Scene",
+    Error = "ConvertError",
+    context = "by_foreign"
+))]
+struct Scene {
+    #[convert(rename(shape))]
+    rect: Rect,
+    #[convert(path = "meta.depth")]
+    depth: u8,
+    #[convert(flatten)]
+    origin: Origin,
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(try_from(
+    Wire("wire::Scene", ignore_rest),
+    Error = "ConvertError",
+    context = "by_foreign"
+))]
+struct Origin {
+    #[convert(rename(x))]
+    left: u8,
+}
+
+// the flattened struct chains the name of its field
+#[derive(Convert, PartialEq, Debug)]
+#[convert(try_from(
+    Wire = "wire::Scene",
+    Error = "ConvertError",
+    context = "by_field"
+))]
+struct Frame {
+    #[convert(flatten)]
+    origin: FrameOrigin,
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(try_from(
+    Wire("wire::Scene", ignore_rest),
+    Error = "ConvertError",
+    context = "by_field"
+))]
+struct FrameOrigin {
+    #[convert(rename(x))]
+    left: u8,
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(try_from(
+    Wire = "wire::Rect",
+    Error = "ConvertError",
+    context = "by_foreign"
+))]
+struct Rect {
+    #[convert(try_map = "colors")]
+    colors: Vec<Color>,
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(try_from(
+    Wire = "wire::Color",
+    Paint("wire::Paint", context = "by_field"),
+    Error = "ConvertError",
+    context = "by_foreign"
+))]
+struct Color {
+    #[convert(rename(r))]
+    red: u8,
+}
+
+fn colors(colors: Vec<wire::Color>) -> Result<Vec<Color>, ConvertError> {
+    colors
+        .into_iter()
+        .enumerate()
+        .map(|(index, color)| {
+            Color::try_from(color)
+                .map_err(|error| error.at(&format!("[{}]", index)))
+        })
+        .collect()
+}
+
+mod wire {
+    #[derive(Clone)]
+    pub struct Scene {
+        pub shape: Rect,
+        pub meta: Meta,
+        pub x: u16,
+    }
+
+    #[derive(Clone)]
+    pub struct Meta {
+        pub depth: u16,
+    }
+
+    #[derive(Clone)]
+    pub struct Rect {
+        pub colors: Vec<Color>,
+    }
+
+    #[derive(Clone)]
+    pub struct Color {
+        pub r: u16,
+    }
+
+    pub struct Paint {
+        pub r: u16,
+    }
+}
+
+#[test]
+fn context_path() {
+    let color = |r| wire::Color { r };
+    let scene = |colors, depth, x| wire::Scene {
+        shape: wire::Rect { colors },
+        meta: wire::Meta { depth },
+        x,
+    };
+    assert_eq!(
+        Scene::try_from(scene(vec![color(1), color(2)], 3, 4)),
+        Ok(Scene {
+            rect: Rect {
+                colors: vec![Color { red: 1 }, Color { red: 2 }],
+            },
+            depth: 3,
+            origin: Origin { left: 4 },
+        })
+    );
+    let error =
+        Scene::try_from(scene(vec![color(1), color(256)], 3, 4)).unwrap_err();
+    assert_eq!(error.path, "shape.colors[1].r");
+    let error = Scene::try_from(scene(vec![], 256, 4)).unwrap_err();
+    assert_eq!(error.path, "meta.depth");
+    let error = Scene::try_from(scene(vec![], 3, 256)).unwrap_err();
+    assert_eq!(error.path, "x");
+    let error = Frame::try_from(scene(vec![], 3, 256)).unwrap_err();
+    assert_eq!(error.path, "origin.left");
+}
+
+#[test]
+fn context_key() {
+    let error = Color::try_from(wire::Paint { r: 256 }).unwrap_err();
+    assert_eq!(error.path, "red");
+}
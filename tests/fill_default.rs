@@ -0,0 +1,98 @@
+use derive_convert::Convert;
+
+#[derive(Convert, Default, PartialEq, Debug)]
+#[convert(
+    from(V1("v1::Settings", ignore("color"), fill_default)),
+    from_self(V1("v1::Settings", fill_default)),
+    try_from(
+        V2("v2::Settings", fill_default = "Settings::fallback()"),
+        Error = "std::num::TryFromIntError"
+    )
+)]
+struct Settings {
+    name: String,
+    retries: u8,
+    #[convert(skip, default)]
+    verbose: bool,
+    #[convert(skip, default)]
+    timeout: u32,
+}
+
+impl Settings {
+    fn fallback() -> Self {
+        Self {
+            verbose: true,
+            timeout: 30,
+            ..Default::default()
+        }
+    }
+}
+
+mod v1 {
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Settings {
+        pub name: String,
+        pub retries: u8,
+        pub color: bool,
+    }
+}
+
+mod v2 {
+    pub struct Settings {
+        pub name: String,
+        pub retries: u16,
+    }
+}
+
+#[test]
+fn fill_default_from() {
+    let settings = Settings::from(v1::Settings {
+        name: "main".into(),
+        retries: 3,
+        color: true,
+    });
+    assert_eq!(
+        settings,
+        Settings {
+            name: "main".into(),
+            retries: 3,
+            verbose: false,
+            timeout: 0,
+        }
+    );
+}
+
+#[test]
+fn fill_default_from_self() {
+    let settings = v1::Settings::from(Settings {
+        name: "main".into(),
+        retries: 3,
+        verbose: true,
+        timeout: 10,
+    });
+    assert_eq!(
+        settings,
+        v1::Settings {
+            name: "main".into(),
+            retries: 3,
+            color: false,
+        }
+    );
+}
+
+#[test]
+fn fill_default_expr() {
+    let settings = Settings::try_from(v2::Settings {
+        name: "main".into(),
+        retries: 3,
+    });
+    assert_eq!(
+        settings,
+        Ok(Settings {
+            name: "main".into(),
+            retries: 3,
+            verbose: true,
+            timeout: 30,
+        })
+    );
+}
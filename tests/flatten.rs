@@ -0,0 +1,119 @@
+use derive_convert::Convert;
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(
+    from(Dto = "RectDto"),
+    from_self(Dto = "RectDto"),
+    try_from(Wide = "WideRectDto", Error = "std::num::TryFromIntError")
+)]
+struct Rect {
+    #[convert(flatten)]
+    origin: Point,
+    #[convert(flatten(w, h))]
+    size: Size,
+    label: String,
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(
+    from(Dto("RectDto", ignore_rest)),
+    from_self(Dto("RectDto", fill_default)),
+    try_from(
+        Wide("WideRectDto", ignore_rest),
+        Error = "std::num::TryFromIntError"
+    )
+)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(
+    from(Dto("RectDto", ignore_rest)),
+    from_self(Dto("RectDto", fill_default)),
+    try_from(
+        Wide("WideRectDto", ignore_rest),
+        Error = "std::num::TryFromIntError"
+    )
+)]
+struct Size {
+    w: u32,
+    h: u32,
+}
+
+#[derive(Clone, Default, PartialEq, Debug)]
+struct RectDto {
+    x: i32,
+    y: i32,
+    w: u32,
+    h: u32,
+    label: String,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct WideRectDto {
+    x: i64,
+    y: i64,
+    w: u64,
+    h: u64,
+    label: String,
+}
+
+#[test]
+fn flatten_from() {
+    let rect = Rect::from(RectDto {
+        x: 1,
+        y: 2,
+        w: 3,
+        h: 4,
+        label: "a".into(),
+    });
+    assert_eq!(
+        rect,
+        Rect {
+            origin: Point { x: 1, y: 2 },
+            size: Size { w: 3, h: 4 },
+            label: "a".into(),
+        }
+    );
+}
+
+#[test]
+fn flatten_try_from() {
+    let dto = WideRectDto {
+        x: 1,
+        y: 2,
+        w: 3,
+        h: 4,
+        label: "a".into(),
+    };
+    assert_eq!(
+        Rect::try_from(dto.clone()),
+        Ok(Rect {
+            origin: Point { x: 1, y: 2 },
+            size: Size { w: 3, h: 4 },
+            label: "a".into(),
+        })
+    );
+    assert!(Rect::try_from(WideRectDto { x: 1 << 40, ..dto }).is_err());
+}
+
+#[test]
+fn flatten_from_self() {
+    let dto = RectDto::from(Rect {
+        origin: Point { x: 1, y: 2 },
+        size: Size { w: 3, h: 4 },
+        label: "a".into(),
+    });
+    assert_eq!(
+        dto,
+        RectDto {
+            x: 1,
+            y: 2,
+            w: 3,
+            h: 4,
+            label: "a".into(),
+        }
+    );
+}
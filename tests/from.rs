@@ -1,12 +1,13 @@
 use derive_convert::Convert;
 
 #[derive(Convert)]
-#[from(V1 = "Color1")]
+#[convert(from(V1 = "Color1"))]
+#[allow(dead_code)]
 enum Color {
     Red,
-    #[from(V1(rename("Blues")))]
+    #[convert(from(V1(rename("Blues"))))]
     Blue,
-    #[from(V1(skip))]
+    #[convert(from(V1(skip)))]
     Green,
 }
 
@@ -17,7 +18,7 @@ enum Color1 {
 }
 
 #[derive(Convert)]
-#[from_self(COMMON = "Color")]
+#[convert(from_self(COMMON = "Color"))]
 #[allow(dead_code)]
 enum Color2 {
     Red,
@@ -26,15 +27,16 @@ enum Color2 {
 }
 
 #[derive(Convert)]
-#[from(V1("Foo1", ignore("_c")))]
+#[convert(from(V1("Foo1", ignore("_c"))))]
 #[allow(dead_code)]
 struct Foo {
     a: i32,
     b: String,
-    #[from(V1(default))]
+    #[convert(from(V1(default)))]
     d: f64,
 }
 
+#[allow(dead_code)]
 struct Foo1 {
     a: i32,
     b: String,
@@ -42,7 +44,8 @@ struct Foo1 {
 }
 
 #[derive(Convert)]
-#[from_self(COMMON = "Foo")]
+#[convert(from_self(COMMON = "Foo"))]
+#[allow(dead_code)]
 struct Foo2 {
     a: i32,
     b: String,
@@ -0,0 +1,119 @@
+use std::marker::PhantomData;
+
+use derive_convert::Convert;
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(from(V1 = "Page1<T>", V2 = "Page2<T>"))]
+struct Page<T>
+where
+    T: Clone,
+{
+    items: Vec<T>,
+    #[convert(from(V1(default), V2(rename("len"))))]
+    total: usize,
+}
+
+struct Page1<T> {
+    items: Vec<T>,
+}
+
+struct Page2<T> {
+    items: Vec<T>,
+    len: usize,
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(from(V1 = "Wrapped1<U>", bound = "T: From<U>"))]
+struct Wrapped<T, U> {
+    value: T,
+    #[convert(from(V1(default)))]
+    marker: PhantomData<U>,
+}
+
+struct Wrapped1<U> {
+    value: U,
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(from_self(V1("Envelope1<'a, T>", bound("T: Copy"))))]
+struct Envelope<'a, T> {
+    tag: &'a str,
+    body: T,
+}
+
+#[derive(PartialEq, Debug)]
+struct Envelope1<'a, T> {
+    tag: &'a str,
+    body: T,
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(from(V1 = "Buffer1<N>"))]
+struct Buffer<const N: usize> {
+    data: [u8; N],
+}
+
+struct Buffer1<const N: usize> {
+    data: [u8; N],
+}
+
+#[test]
+fn generic_struct() {
+    let page1 = Page1 {
+        items: vec![1, 2, 3],
+    };
+    let page: Page<i32> = page1.into();
+    assert_eq!(
+        page,
+        Page {
+            items: vec![1, 2, 3],
+            total: 0,
+        }
+    );
+    let page2 = Page2 {
+        items: vec!["a"],
+        len: 1,
+    };
+    let page: Page<&str> = page2.into();
+    assert_eq!(
+        page,
+        Page {
+            items: vec!["a"],
+            total: 1,
+        }
+    );
+}
+
+#[test]
+fn generic_struct_with_bound() {
+    let wrapped: Wrapped<i64, i32> = Wrapped1 { value: 1 }.into();
+    assert_eq!(
+        wrapped,
+        Wrapped {
+            value: 1,
+            marker: PhantomData,
+        }
+    );
+}
+
+#[test]
+fn lifetime_struct() {
+    let envelope = Envelope {
+        tag: "foo",
+        body: 1,
+    };
+    let envelope1: Envelope1<i32> = envelope.into();
+    assert_eq!(
+        envelope1,
+        Envelope1 {
+            tag: "foo",
+            body: 1,
+        }
+    );
+}
+
+#[test]
+fn const_generic_struct() {
+    let buffer: Buffer<2> = Buffer1 { data: [1, 2] }.into();
+    assert_eq!(buffer, Buffer { data: [1, 2] });
+}
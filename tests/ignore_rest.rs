@@ -0,0 +1,112 @@
+use derive_convert::Convert;
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(from(
+    Gen("gen::Account", ignore_rest),
+    Tuple("gen::Pair", ignore_rest)
+))]
+struct Account {
+    #[convert(Tuple(index = 0))]
+    id: u64,
+    #[convert(Tuple(index = 1))]
+    owner: String,
+}
+
+#[allow(dead_code)]
+mod gen {
+    pub struct Account {
+        pub id: u64,
+        pub owner: String,
+        pub created_at: u64,
+        pub updated_at: u64,
+        pub etag: String,
+    }
+
+    pub struct Pair(pub u64, pub String, pub bool);
+}
+
+#[test]
+fn ignore_rest_struct() {
+    let account = Account::from(gen::Account {
+        id: 7,
+        owner: "ada".into(),
+        created_at: 1,
+        updated_at: 2,
+        etag: "x".into(),
+    });
+    assert_eq!(
+        account,
+        Account {
+            id: 7,
+            owner: "ada".into(),
+        }
+    );
+}
+
+#[test]
+fn ignore_rest_tuple_struct() {
+    let account = Account::from(gen::Pair(7, "ada".into(), true));
+    assert_eq!(
+        account,
+        Account {
+            id: 7,
+            owner: "ada".into(),
+        }
+    );
+}
+
+// `ignore_rest(warn)` names the fields it ignores, and warns about the others
+#[derive(Convert, PartialEq, Debug)]
+#[convert(from(Gen(
+    "gen::Account",
+    ignore("created_at", "updated_at", "etag"),
+    ignore_rest(warn)
+)))]
+struct Owner {
+    id: u64,
+    owner: String,
+}
+
+#[allow(deprecated)]
+mod added {
+    use derive_convert::Convert;
+
+    use super::gen;
+
+    // `gen::Account` gained `etag`, a warning rather than an error
+    #[derive(Convert, PartialEq, Debug)]
+    #[convert(from(Gen(
+        "gen::Account",
+        ignore("created_at", "updated_at"),
+        ignore_rest(warn)
+    )))]
+    pub struct Owner {
+        pub id: u64,
+        pub owner: String,
+    }
+}
+
+#[test]
+fn ignore_rest_warn() {
+    let account = || gen::Account {
+        id: 7,
+        owner: "ada".into(),
+        created_at: 1,
+        updated_at: 2,
+        etag: "x".into(),
+    };
+    assert_eq!(
+        Owner::from(account()),
+        Owner {
+            id: 7,
+            owner: "ada".into(),
+        }
+    );
+    assert_eq!(
+        added::Owner::from(account()),
+        added::Owner {
+            id: 7,
+            owner: "ada".into(),
+        }
+    );
+}
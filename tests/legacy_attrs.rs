@@ -0,0 +1,30 @@
+#![cfg(feature = "legacy-attrs")]
+
+use derive_convert::Convert;
+
+#[derive(Convert, PartialEq, Debug)]
+#[from(V1 = "Size1")]
+#[convert(from_self(V1 = "Size1"))]
+struct Size {
+    #[from(V1(rename("w")))]
+    #[convert(from_self(V1(rename("w"))))]
+    width: u32,
+    height: u32,
+}
+
+#[derive(PartialEq, Debug)]
+struct Size1 {
+    w: u32,
+    height: u32,
+}
+
+#[test]
+fn legacy_and_namespaced() {
+    let size1 = Size1 { w: 1, height: 2 };
+    let size = Size {
+        width: 1,
+        height: 2,
+    };
+    assert_eq!(Size::from(size1), size);
+    assert_eq!(Size1::from(size), Size1 { w: 1, height: 2 });
+}
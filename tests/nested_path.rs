@@ -0,0 +1,102 @@
+use derive_convert::Convert;
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(
+    from(Wire = "wire::Event"),
+    from_self(Wire = "wire::Event"),
+    try_from(Wide = "wire::WideEvent", Error = "std::num::TryFromIntError")
+)]
+struct Event {
+    id: u64,
+    #[convert(path = "meta.created_at")]
+    created_at: u64,
+    #[convert(path = "meta.author.name")]
+    author: String,
+    #[convert(Wire(path = "meta.tags"), Wide(rename(tags)))]
+    tags: Vec<String>,
+}
+
+mod wire {
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Event {
+        pub id: u64,
+        pub meta: Meta,
+    }
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Meta {
+        pub created_at: u64,
+        pub author: Author,
+        pub tags: Vec<String>,
+        pub revision: u32,
+    }
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Author {
+        pub name: String,
+    }
+
+    pub struct WideEvent {
+        pub id: u128,
+        pub meta: WideMeta,
+        pub tags: Vec<String>,
+    }
+
+    pub struct WideMeta {
+        pub created_at: u128,
+        pub author: Author,
+    }
+}
+
+fn event() -> Event {
+    Event {
+        id: 1,
+        created_at: 2,
+        author: "ada".into(),
+        tags: vec!["a".into()],
+    }
+}
+
+#[test]
+fn nested_path_from() {
+    let wire = wire::Event {
+        id: 1,
+        meta: wire::Meta {
+            created_at: 2,
+            author: wire::Author { name: "ada".into() },
+            tags: vec!["a".into()],
+            revision: 3,
+        },
+    };
+    assert_eq!(Event::from(wire), event());
+}
+
+#[test]
+fn nested_path_try_from() {
+    let wide = wire::WideEvent {
+        id: 1,
+        meta: wire::WideMeta {
+            created_at: 2,
+            author: wire::Author { name: "ada".into() },
+        },
+        tags: vec!["a".into()],
+    };
+    assert_eq!(Event::try_from(wide), Ok(event()));
+}
+
+#[test]
+fn nested_path_from_self() {
+    let wire = wire::Event::from(event());
+    assert_eq!(
+        wire,
+        wire::Event {
+            id: 1,
+            meta: wire::Meta {
+                created_at: 2,
+                author: wire::Author { name: "ada".into() },
+                tags: vec!["a".into()],
+                revision: 0,
+            },
+        }
+    );
+}
@@ -0,0 +1,111 @@
+use derive_convert::Convert;
+
+mod v1 {
+    #[allow(dead_code)]
+    #[derive(Debug, PartialEq)]
+    pub enum ErrorCode {
+        NotFound = 404,
+        Internal = 500,
+        Teapot = 418,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, PartialEq)]
+    pub enum Status {
+        Active = 0,
+        Blocked = 1,
+        Deleted = 2,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, PartialEq)]
+    pub enum Level {
+        Trace,
+        Debug,
+        Info,
+        Warn,
+    }
+}
+
+#[derive(Convert, Debug, PartialEq)]
+#[convert(
+    from(V1("v1::ErrorCode", pair_by = "discriminant", fallback = "Unknown")),
+    from_self(V1("v1::ErrorCode", pair_by = "discriminant"))
+)]
+enum ErrorCode {
+    #[convert(from_self(rename(NotFound)))]
+    Missing = 404,
+    #[convert(from_self(rename(Internal)))]
+    ServerError = 500,
+    #[convert(from(skip), from_self(rename(Teapot)))]
+    Unknown = 418,
+}
+
+#[test]
+fn pair_by_discriminant() {
+    assert_eq!(ErrorCode::from(v1::ErrorCode::NotFound), ErrorCode::Missing);
+    assert_eq!(
+        ErrorCode::from(v1::ErrorCode::Internal),
+        ErrorCode::ServerError
+    );
+    assert_eq!(ErrorCode::from(v1::ErrorCode::Teapot), ErrorCode::Unknown);
+    assert_eq!(
+        v1::ErrorCode::from(ErrorCode::Missing),
+        v1::ErrorCode::NotFound
+    );
+}
+
+#[derive(Convert, Debug, PartialEq)]
+#[convert(
+    try_from(
+        V1("v1::Level", pair_by = "discriminant"),
+        Error = "UnknownLevel",
+        error = "UnknownLevel"
+    ),
+    try_from_self(
+        V1(
+            "v1::Level",
+            pair_by = "index",
+            variants("Trace", "Debug", "Info", "Warn")
+        ),
+        Error = "UnknownLevel"
+    )
+)]
+enum Level {
+    #[convert(try_from(value = 0))]
+    Low = 10,
+    #[convert(try_from(value = 1))]
+    Medium = 20,
+    #[convert(try_from(value = 3), try_from_self(error = "UnknownLevel"))]
+    High = 30,
+}
+
+#[derive(Debug, PartialEq)]
+struct UnknownLevel;
+
+#[test]
+fn pair_by_index() {
+    assert_eq!(Level::try_from(v1::Level::Trace), Ok(Level::Low));
+    assert_eq!(Level::try_from(v1::Level::Debug), Ok(Level::Medium));
+    assert_eq!(Level::try_from(v1::Level::Warn), Ok(Level::High));
+    assert_eq!(Level::try_from(v1::Level::Info), Err(UnknownLevel));
+    assert_eq!(v1::Level::try_from(Level::Low), Ok(v1::Level::Trace));
+    assert_eq!(v1::Level::try_from(Level::Medium), Ok(v1::Level::Debug));
+    assert_eq!(v1::Level::try_from(Level::High), Err(UnknownLevel));
+}
+
+// explicit foreign discriminants pair by index when they are the
+// declaration indexes
+#[derive(Convert, Debug, PartialEq)]
+#[convert(from_self(V1("v1::Status", pair_by = "index")))]
+enum Status {
+    Active,
+    #[convert(rename(Blocked))]
+    Suspended,
+}
+
+#[test]
+fn pair_by_index_explicit() {
+    assert_eq!(v1::Status::from(Status::Active), v1::Status::Active);
+    assert_eq!(v1::Status::from(Status::Suspended), v1::Status::Blocked);
+}
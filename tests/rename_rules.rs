@@ -0,0 +1,119 @@
+use derive_convert::Convert;
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(
+    from(PB(
+        "pb::Color",
+        rename_all = "SCREAMING_SNAKE_CASE",
+        prefix = "COLOR_"
+    )),
+    from_self(PB(
+        "pb::Color",
+        rename_all = "SCREAMING_SNAKE_CASE",
+        prefix = "COLOR_"
+    ))
+)]
+enum Color {
+    Red,
+    DarkBlue,
+    #[convert(PB(rename(COLOR_UNKNOWN)))]
+    Other,
+}
+
+#[allow(non_camel_case_types)]
+mod pb {
+    #[derive(PartialEq, Debug)]
+    pub enum Color {
+        COLOR_RED,
+        COLOR_DARK_BLUE,
+        COLOR_UNKNOWN,
+    }
+}
+
+#[test]
+fn rename_all_prefix_enum() {
+    assert_eq!(Color::from(pb::Color::COLOR_DARK_BLUE), Color::DarkBlue);
+    assert_eq!(Color::from(pb::Color::COLOR_UNKNOWN), Color::Other);
+    assert_eq!(pb::Color::from(Color::Red), pb::Color::COLOR_RED);
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(
+    from(Gen("gen::User", rename_all = "camelCase")),
+    from_self(Gen("gen::User", rename_all = "camelCase")),
+    from(Raw("gen::RawUser", suffix = "_raw"))
+)]
+struct User {
+    user_name: String,
+    #[convert(Gen(rename(id)))]
+    user_id: u32,
+    #[convert(from(Gen(default)), from_self(Gen(skip)))]
+    #[convert(Raw(rename(created_raw)))]
+    created: u64,
+}
+
+mod gen {
+    #[allow(non_snake_case)]
+    #[derive(PartialEq, Debug)]
+    pub struct User {
+        pub userName: String,
+        pub id: u32,
+    }
+
+    pub struct RawUser {
+        pub user_name_raw: String,
+        pub user_id_raw: u32,
+        pub created_raw: u64,
+    }
+}
+
+#[test]
+fn rename_all_suffix_struct() {
+    let user = User::from(gen::User {
+        userName: "ann".into(),
+        id: 1,
+    });
+    assert_eq!(
+        user,
+        User {
+            user_name: "ann".into(),
+            user_id: 1,
+            created: 0,
+        }
+    );
+    assert_eq!(
+        gen::User::from(user),
+        gen::User {
+            userName: "ann".into(),
+            id: 1,
+        }
+    );
+    let user = User::from(gen::RawUser {
+        user_name_raw: "bob".into(),
+        user_id_raw: 2,
+        created_raw: 3,
+    });
+    assert_eq!(user.created, 3);
+}
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(from_self(Lower("lower::Keyword", rename_all = "lowercase")))]
+enum Keyword {
+    Fn,
+    Let,
+}
+
+#[allow(non_camel_case_types)]
+mod lower {
+    #[derive(PartialEq, Debug)]
+    pub enum Keyword {
+        r#fn,
+        r#let,
+    }
+}
+
+#[test]
+fn rename_all_into_keyword() {
+    assert_eq!(lower::Keyword::from(Keyword::Fn), lower::Keyword::r#fn);
+    assert_eq!(lower::Keyword::from(Keyword::Let), lower::Keyword::r#let);
+}
@@ -1,14 +1,14 @@
 use derive_convert::Convert;
 
 #[derive(Convert, PartialEq, Debug)]
-#[try_from(V1 = "Color1", V2 = "v2::Color2", Error = "()")]
+#[convert(try_from(V1 = "Color1", V2 = "v2::Color2", Error = "()"))]
 enum Color {
     Red,
-    #[try_from(V1(rename("Blues")))]
+    #[convert(try_from(V1(rename("Blues"))))]
     Blue,
-    #[try_from(V1(skip))]
+    #[convert(try_from(V1(skip)))]
     Green,
-    #[try_from(skip)]
+    #[convert(try_from(skip))]
     #[allow(dead_code)]
     Black,
 }
@@ -0,0 +1,48 @@
+use derive_convert::Convert;
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(try_from(
+    V1("Shade1", ignore("Purple", "Orange"), error = "Error::Unknown"),
+    V2("Shade2", fallback = "Other", non_exhaustive),
+    Error = "Error"
+))]
+enum Shade {
+    Light,
+    Dark,
+    #[convert(try_from(V1(skip), V2(skip)))]
+    Other,
+}
+
+#[derive(PartialEq, Debug)]
+enum Error {
+    Unknown,
+}
+
+#[allow(dead_code)]
+enum Shade1 {
+    Light,
+    Dark,
+    Purple,
+    Orange,
+}
+
+#[non_exhaustive]
+#[allow(dead_code)]
+enum Shade2 {
+    Light,
+    Dark,
+    Pink,
+}
+
+#[test]
+fn unmapped_error() {
+    assert_eq!(Shade::try_from(Shade1::Dark), Ok(Shade::Dark));
+    assert_eq!(Shade::try_from(Shade1::Purple), Err(Error::Unknown));
+    assert_eq!(Shade::try_from(Shade1::Orange), Err(Error::Unknown));
+}
+
+#[test]
+fn unmapped_fallback() {
+    assert_eq!(Shade::try_from(Shade2::Light), Ok(Shade::Light));
+    assert_eq!(Shade::try_from(Shade2::Pink), Ok(Shade::Other));
+}
@@ -15,9 +15,9 @@ where
 }
 
 #[derive(Convert, Debug)]
-#[try_from(V1("&Foo1"), Error = "Error")]
+#[convert(try_from(V1("&Foo1"), Error = "Error"))]
 pub struct Foo {
-    #[try_from(try_map_ref = "ref_try_into_opt")]
+    #[convert(try_from(try_map_ref = "ref_try_into_opt"))]
     pub bar: Option<String>,
 }
 
@@ -1,10 +1,12 @@
+use std::convert::Infallible;
+
 use derive_convert::Convert;
 
 #[derive(Convert, PartialEq, Debug)]
-#[try_from_self(V1 = "Color1", V2 = "v2::Color2", Error = "()")]
+#[convert(try_from_self(V1 = "Color1", V2 = "v2::Color2", Error = "()"))]
 enum Color {
     Red,
-    #[try_from_self(V1(rename("Blues")))]
+    #[convert(try_from_self(V1(rename("Blues"))))]
     Blue,
 }
 
@@ -31,3 +33,62 @@ fn try_from_self_enum() {
     let color2: v2::Color2 = Color::Blue.try_into().unwrap();
     assert_eq!(color2, v2::Color2::Blue);
 }
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(try_from_self(
+    V1 = "Status1",
+    V2("Status2", error = "Error::Removed"),
+    Error = "Error",
+    error = "Error::Unsupported"
+))]
+enum Status {
+    Active,
+    #[convert(try_from_self(V1(error = "Error::Pending")))]
+    Pending {
+        since: u32,
+    },
+    #[convert(try_from_self(skip))]
+    Archived(u32),
+}
+
+#[derive(PartialEq, Debug)]
+enum Error {
+    Unsupported,
+    Pending,
+    Removed,
+}
+
+impl From<Infallible> for Error {
+    fn from(infallible: Infallible) -> Self {
+        match infallible {}
+    }
+}
+
+#[derive(PartialEq, Debug)]
+enum Status1 {
+    Active,
+}
+
+#[derive(PartialEq, Debug)]
+enum Status2 {
+    Active,
+    Pending { since: u32 },
+}
+
+#[test]
+fn try_from_self_enum_errors() {
+    assert_eq!(Status1::try_from(Status::Active), Ok(Status1::Active));
+    assert_eq!(
+        Status1::try_from(Status::Pending { since: 1 }),
+        Err(Error::Pending)
+    );
+    assert_eq!(
+        Status1::try_from(Status::Archived(1)),
+        Err(Error::Unsupported)
+    );
+    assert_eq!(
+        Status2::try_from(Status::Pending { since: 1 }),
+        Ok(Status2::Pending { since: 1 })
+    );
+    assert_eq!(Status2::try_from(Status::Archived(1)), Err(Error::Removed));
+}
@@ -3,24 +3,24 @@ use std::{convert::Infallible, num::TryFromIntError};
 use derive_convert::Convert;
 
 #[derive(Convert, PartialEq, Debug)]
-#[try_from_self(V1 = "Rect1", V2 = "v2::Rect2", Error = "Error")]
+#[convert(try_from_self(V1 = "Rect1", V2 = "v2::Rect2", Error = "Error"))]
 struct Rect {
-    #[try_from_self(V2(skip))]
+    #[convert(try_from_self(V2(skip)))]
     tag: String,
     x: i32,
-    #[try_from_self(V1(try_map = "try_some"), V2(skip))]
+    #[convert(try_from_self(V1(try_map = "try_some"), V2(skip)))]
     y: Option<i32>,
     z: i32,
-    #[try_from_self(map = "|a: Foo<_>| a.0")]
+    #[convert(try_from_self(map = "|a: Foo<_>| a.0"))]
     width: Foo<i32>,
-    #[try_from_self(try_map = "try_some", rename(Height))]
+    #[convert(try_from_self(try_map = "try_some", rename(Height)))]
     height: Option<i32>,
-    #[try_from_self(
+    #[convert(try_from_self(
         V1(
             try_map = "|vec: Vec<i64>| vec.into_iter().map(|val| val.try_into()).collect::<Result<Vec<i32>, _>>()"
         ),
         V2(skip)
-    )]
+    ))]
     colors: Vec<i64>,
 }
 
@@ -3,28 +3,28 @@ use std::{convert::Infallible, num::TryFromIntError};
 use derive_convert::Convert;
 
 #[derive(Convert, PartialEq, Debug)]
-#[try_from(
+#[convert(try_from(
     V1("Rect1", ignore("_other1")),
     V2("v2::Rect2", ignore("_other2")),
     Error = "Error"
-)]
+))]
 struct Rect {
-    #[try_from(V2(new = "String::new"))]
+    #[convert(try_from(V2(new = "String::new")))]
     tag: String,
     x: i32,
-    #[try_from(V2(default))]
+    #[convert(try_from(V2(default)))]
     y: Option<i32>,
     z: i32,
-    #[try_from(map = "Foo")]
+    #[convert(try_from(map = "Foo"))]
     width: Foo<i32>,
-    #[try_from(V2(rename("Height")))]
+    #[convert(try_from(V2(rename("Height"))))]
     height: Option<i32>,
-    #[try_from(
+    #[convert(try_from(
         V1(
             try_map = "|vec: Vec<i64>| vec.into_iter().map(|val| val.try_into()).collect::<Result<Vec<i32>, _>>()"
         ),
         V2(default)
-    )]
+    ))]
     colors: Vec<i32>,
 }
 
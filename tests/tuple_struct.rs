@@ -0,0 +1,88 @@
+use std::{convert::Infallible, num::TryFromIntError};
+
+use derive_convert::Convert;
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(from(V1 = "Meters1"), from_self(V1 = "Meters1"))]
+struct Meters(f64);
+
+#[derive(PartialEq, Debug)]
+struct Meters1(f64);
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(try_from(V1 = "Point1", V2("Point2", ignore("2")), Error = "Error"))]
+#[convert(from_self(V1 = "Point1"))]
+struct Point(
+    #[convert(try_from(V1(rename("x"))))]
+    #[convert(from_self(V1(rename("x"))))]
+    i32,
+    #[convert(try_from(V1(rename("y"))))]
+    #[convert(from_self(V1(rename("y"))))]
+    i32,
+);
+
+#[derive(PartialEq, Debug)]
+struct Point1 {
+    x: i32,
+    y: i32,
+}
+
+struct Point2(i64, i64, ());
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(from(V2("Point2", ignore("2"))))]
+#[convert(from_self(V3 = "Point3"))]
+struct NamedPoint {
+    #[convert(from(index = 0))]
+    #[convert(from_self(V3(index = 1)))]
+    x: i64,
+    #[convert(from(index = 1))]
+    #[convert(from_self(V3(index = 0)))]
+    y: i64,
+}
+
+#[derive(PartialEq, Debug)]
+struct Point3(i64, i64);
+
+#[derive(Debug)]
+enum Error {
+    TryFromInt,
+}
+
+impl From<Infallible> for Error {
+    fn from(value: Infallible) -> Self {
+        match value {}
+    }
+}
+
+impl From<TryFromIntError> for Error {
+    fn from(_: TryFromIntError) -> Self {
+        Self::TryFromInt
+    }
+}
+
+#[test]
+fn newtype() {
+    assert_eq!(Meters::from(Meters1(1.5)), Meters(1.5));
+    assert_eq!(Meters1::from(Meters(2.5)), Meters1(2.5));
+}
+
+#[test]
+fn tuple_to_named() {
+    let point = Point::try_from(Point1 { x: 1, y: 2 }).unwrap();
+    assert_eq!(point, Point(1, 2));
+    assert_eq!(Point1::from(point), Point1 { x: 1, y: 2 });
+}
+
+#[test]
+fn tuple_to_tuple() {
+    assert_eq!(Point::try_from(Point2(3, 4, ())).unwrap(), Point(3, 4));
+    assert!(Point::try_from(Point2(i64::MAX, 4, ())).is_err());
+}
+
+#[test]
+fn named_to_tuple() {
+    let point = NamedPoint::from(Point2(5, 6, ()));
+    assert_eq!(point, NamedPoint { x: 5, y: 6 });
+    assert_eq!(Point3::from(point), Point3(6, 5));
+}
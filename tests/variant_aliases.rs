@@ -0,0 +1,53 @@
+use derive_convert::Convert;
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(
+    from(V1 = "Hue1"),
+    from_self(V1 = "Hue1"),
+    try_from(V2 = "Hue2", Error = "std::num::TryFromIntError")
+)]
+enum Hue {
+    #[convert(aliases("Crimson", "Scarlet"))]
+    Red,
+    #[convert(try_from(V2(rename("Navy"), aliases(Cobalt))))]
+    Blue { depth: u8 },
+}
+
+#[derive(PartialEq, Debug)]
+enum Hue1 {
+    Red,
+    Crimson,
+    Scarlet,
+    Blue { depth: u8 },
+}
+
+#[allow(dead_code)]
+#[derive(PartialEq, Debug)]
+enum Hue2 {
+    Red,
+    Crimson,
+    Scarlet,
+    Navy { depth: u8 },
+    Cobalt { depth: u8 },
+}
+
+#[test]
+fn aliases_from() {
+    assert_eq!(Hue::from(Hue1::Crimson), Hue::Red);
+    assert_eq!(Hue::from(Hue1::Scarlet), Hue::Red);
+    assert_eq!(Hue::from(Hue1::Blue { depth: 1 }), Hue::Blue { depth: 1 });
+    assert_eq!(Hue1::from(Hue::Red), Hue1::Red);
+}
+
+#[test]
+fn aliases_try_from() {
+    assert_eq!(Hue::try_from(Hue2::Scarlet), Ok(Hue::Red));
+    assert_eq!(
+        Hue::try_from(Hue2::Cobalt { depth: 2 }),
+        Ok(Hue::Blue { depth: 2 })
+    );
+    assert_eq!(
+        Hue::try_from(Hue2::Navy { depth: 3 }),
+        Ok(Hue::Blue { depth: 3 })
+    );
+}
@@ -0,0 +1,76 @@
+use derive_convert::Convert;
+
+#[derive(Convert, PartialEq, Debug)]
+#[convert(
+    from(V1 = "Paint1"),
+    from_self(V1 = "Paint1"),
+    try_from_self(V2 = "Paint2", Error = "Error")
+)]
+enum Paint {
+    Rgb(u8, u8, u8),
+    #[convert(from_self(V1(map = "|_| Paint1::Rgb(0, 0, 0)")))]
+    #[convert(from(V1(
+        map = "|_| Paint::Rgb(255, 255, 255)",
+        rename("White")
+    )))]
+    #[convert(try_from_self(V2(try_map = "legacy_paint")))]
+    Legacy,
+    #[convert(try_from_self(V2(map = "named_paint")))]
+    Named {
+        name: String,
+    },
+}
+
+#[derive(PartialEq, Debug)]
+enum Paint1 {
+    Rgb(u8, u8, u8),
+    White,
+    Named { name: String },
+}
+
+#[derive(PartialEq, Debug)]
+enum Paint2 {
+    Rgb(u8, u8, u8),
+    Hex(String),
+}
+
+#[derive(PartialEq, Debug)]
+struct Error;
+
+impl From<std::convert::Infallible> for Error {
+    fn from(infallible: std::convert::Infallible) -> Self {
+        match infallible {}
+    }
+}
+
+fn legacy_paint(_: Paint) -> Result<Paint2, Error> {
+    Err(Error)
+}
+
+fn named_paint(paint: Paint) -> Paint2 {
+    match paint {
+        Paint::Named { name } => Paint2::Hex(name),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn variant_map_from() {
+    assert_eq!(Paint::from(Paint1::White), Paint::Rgb(255, 255, 255));
+    assert_eq!(Paint1::from(Paint::Legacy), Paint1::Rgb(0, 0, 0));
+}
+
+#[test]
+fn variant_try_map() {
+    assert_eq!(Paint2::try_from(Paint::Legacy), Err(Error));
+    assert_eq!(
+        Paint2::try_from(Paint::Named {
+            name: "name".into()
+        }),
+        Ok(Paint2::Hex("name".into()))
+    );
+    assert_eq!(
+        Paint2::try_from(Paint::Rgb(1, 2, 3)),
+        Ok(Paint2::Rgb(1, 2, 3))
+    );
+}